@@ -0,0 +1,93 @@
+//! Player lasers against enemies, checked pairwise as before and through `SpatialGrid`.
+//!
+//! ```text
+//! cargo bench --bench broadphase
+//! ```
+
+use bevy::ecs::entity::Entity;
+use bevy::math::{Vec2, Vec3};
+use bevy::sprite::collide_aabb::collide;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use invaders::broadphase::SpatialGrid;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const LASER_SIZE: Vec2 = Vec2::new(4.5, 27.);
+const ENEMY_SIZE: Vec2 = Vec2::new(72., 37.5);
+
+struct Body {
+    entity: Entity,
+    position: Vec3,
+    size: Vec2,
+}
+
+/// `lasers` lasers and a tenth as many enemies, scattered over a 676×676 playfield.
+fn scatter(lasers: u32, seed: u64) -> (Vec<Body>, Vec<Body>) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut body = |id: u32, size: Vec2| Body {
+        entity: Entity::from_raw(id),
+        position: Vec3::new(rng.gen_range(-338.0..338.0), rng.gen_range(-338.0..338.0), 0.),
+        size,
+    };
+    let enemies = lasers / 10;
+    let laser_bodies = (0..lasers).map(|id| body(id, LASER_SIZE)).collect();
+    let enemy_bodies = (lasers..lasers + enemies).map(|id| body(id, ENEMY_SIZE)).collect();
+    (laser_bodies, enemy_bodies)
+}
+
+fn pairwise(lasers: &[Body], enemies: &[Body]) -> usize {
+    let mut hits = 0;
+    for laser in lasers {
+        for enemy in enemies {
+            if collide(laser.position, laser.size, enemy.position, enemy.size).is_some() {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+fn gridded(grid: &mut SpatialGrid, lasers: &[Body], enemies: &[Body]) -> usize {
+    grid.clear();
+    for body in lasers.iter().chain(enemies) {
+        grid.insert(body.entity, body.position.truncate(), body.size);
+    }
+
+    let first_enemy = lasers.len() as u32;
+    let mut hits = 0;
+    for laser in lasers {
+        for candidate in grid.query(laser.position.truncate(), laser.size) {
+            let enemy = match candidate.id().checked_sub(first_enemy) {
+                Some(i) => &enemies[i as usize],
+                None => continue,
+            };
+            if collide(laser.position, laser.size, enemy.position, enemy.size).is_some() {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+fn broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("player_lasers_vs_enemies");
+    for lasers in [1_000, 2_000, 4_000] {
+        let (laser_bodies, enemy_bodies) = scatter(lasers, 42);
+        let mut grid = SpatialGrid::default();
+        assert_eq!(
+            pairwise(&laser_bodies, &enemy_bodies),
+            gridded(&mut grid, &laser_bodies, &enemy_bodies)
+        );
+
+        group.bench_with_input(BenchmarkId::new("pairwise", lasers), &lasers, |b, _| {
+            b.iter(|| pairwise(&laser_bodies, &enemy_bodies))
+        });
+        group.bench_with_input(BenchmarkId::new("grid", lasers), &lasers, |b, _| {
+            b.iter(|| gridded(&mut grid, &laser_bodies, &enemy_bodies))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, broadphase);
+criterion_main!(benches);
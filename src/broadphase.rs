@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::collision::WorldShape;
+use crate::components::Collider;
+
+/// Label of the systems moving colliders; `SpatialGrid` is rebuilt after them.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Movement;
+
+/// Label of the system rebuilding `SpatialGrid`; collision systems run after it.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Broadphase;
+
+/// Side of a grid cell in world units, about the size of the largest ships.
+pub const CELL_SIZE: f32 = 96.;
+
+/// Uniform grid of every collider, rebuilt each tick, so collision systems only test
+/// pairs sharing a cell instead of every laser against every ship.
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<Entity>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        SpatialGrid::new(CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    /// Empties the cells, keeping their allocations for the next rebuild.
+    pub fn clear(&mut self) {
+        for entities in self.cells.values_mut() {
+            entities.clear();
+        }
+    }
+
+    /// Adds `entity` to every cell overlapped by the box of `size` centered on `center`.
+    pub fn insert(&mut self, entity: Entity, center: Vec2, size: Vec2) {
+        let (min, max) = self.cell_range(center, size);
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                self.cells.entry((x, y)).or_default().push(entity);
+            }
+        }
+    }
+
+    /// Entities sharing a cell with the box of `size` centered on `center`, each once and
+    /// in a stable order. They are only candidates; the caller does the exact test.
+    pub fn query(&self, center: Vec2, size: Vec2) -> Vec<Entity> {
+        let (min, max) = self.cell_range(center, size);
+        let mut found = Vec::new();
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                if let Some(entities) = self.cells.get(&(x, y)) {
+                    found.extend_from_slice(entities);
+                }
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found
+    }
+
+    fn cell_range(&self, center: Vec2, size: Vec2) -> ((i32, i32), (i32, i32)) {
+        let cell = |point: Vec2| {
+            (
+                (point.x / self.cell_size).floor() as i32,
+                (point.y / self.cell_size).floor() as i32,
+            )
+        };
+        (cell(center - size / 2.), cell(center + size / 2.))
+    }
+}
+
+pub fn spatial_grid_system(
+    mut grid: ResMut<SpatialGrid>,
+    query: Query<(Entity, &Transform, &Collider)>,
+) {
+    grid.clear();
+    for (entity, transform, collider) in query.iter() {
+        let (center, size) = WorldShape::new(collider, transform).bounds();
+        grid.insert(entity, center, size);
+    }
+}
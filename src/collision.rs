@@ -0,0 +1,82 @@
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+
+use crate::broadphase::SpatialGrid;
+use crate::components::{Collider, ColliderShape};
+use crate::events::Collision;
+
+/// A collider placed in the world: translated, scaled and inset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorldShape {
+    Aabb { center: Vec2, size: Vec2 },
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl WorldShape {
+    pub fn new(collider: &Collider, transform: &Transform) -> Self {
+        let center = transform.translation.xy();
+        let scale = transform.scale.xy();
+        match collider.shape {
+            ColliderShape::Aabb(size) => WorldShape::Aabb {
+                center,
+                size: ((size - Vec2::splat(2. * collider.inset)) * scale).max(Vec2::ZERO),
+            },
+            ColliderShape::Circle(radius) => WorldShape::Circle {
+                center,
+                radius: ((radius - collider.inset) * scale.max_element()).max(0.),
+            },
+        }
+    }
+
+    /// Center and size of the smallest box around the shape.
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        match *self {
+            WorldShape::Aabb { center, size } => (center, size),
+            WorldShape::Circle { center, radius } => (center, Vec2::splat(2. * radius)),
+        }
+    }
+
+    pub fn overlaps(&self, other: &WorldShape) -> bool {
+        match (*self, *other) {
+            (WorldShape::Aabb { center: a, size: a_size }, WorldShape::Aabb { center: b, size: b_size }) => {
+                let reach = (a_size + b_size) / 2.;
+                (a - b).abs().cmplt(reach).all()
+            }
+            (WorldShape::Circle { center: a, radius: a_radius }, WorldShape::Circle { center: b, radius: b_radius }) => {
+                a.distance_squared(b) < (a_radius + b_radius) * (a_radius + b_radius)
+            }
+            (WorldShape::Aabb { center, size }, WorldShape::Circle { center: circle, radius })
+            | (WorldShape::Circle { center: circle, radius }, WorldShape::Aabb { center, size }) => {
+                let closest = circle.clamp(center - size / 2., center + size / 2.);
+                closest.distance_squared(circle) < radius * radius
+            }
+        }
+    }
+}
+
+/// Reports every overlapping pair of colliders whose layers interact, once per tick.
+pub fn collision_system(
+    grid: Res<SpatialGrid>,
+    query: Query<(Entity, &Transform, &Collider)>,
+    mut collisions: EventWriter<Collision>,
+) {
+    for (entity, transform, collider) in query.iter() {
+        let shape = WorldShape::new(collider, transform);
+        let (center, size) = shape.bounds();
+        for other in grid.query(center, size) {
+            // Both sides of a pair see each other; only the lower entity reports it.
+            if other <= entity {
+                continue;
+            }
+            let (other_tf, other_collider) = match query.get(other) {
+                Ok((_, other_tf, other_collider)) => (other_tf, other_collider),
+                Err(_) => continue,
+            };
+            if collider.interacts_with(other_collider)
+                && shape.overlaps(&WorldShape::new(other_collider, other_tf))
+            {
+                collisions.send(Collision { a: entity, b: other });
+            }
+        }
+    }
+}
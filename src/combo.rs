@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::config::{ComboConfig, GameConfig};
+use crate::events::{CollisionResponse, EnemyDestroyed, PlayerHit, ScoreAwarded};
+use crate::timestep::FixedUpdateStage;
+use crate::{playing_criteria, AppState, PlayerState, TIME_STEP};
+
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+enum ComboSystem {
+    Decay,
+    Score,
+}
+
+/// The current kill chain. A multiplier of 0 means no chain is running.
+#[derive(Debug, Default)]
+pub struct Combo {
+    pub multiplier: u32,
+    timer: Timer,
+}
+
+impl Combo {
+    /// Registers a kill and returns the multiplier it scores with.
+    pub fn kill(&mut self, config: &ComboConfig) -> u32 {
+        self.multiplier = (self.multiplier + 1).min(config.max_multiplier);
+        self.timer = Timer::from_seconds(config.window, true);
+        self.multiplier
+    }
+
+    /// Advances the chain; every window without a kill drops the multiplier by one.
+    pub fn tick(&mut self, delta: Duration) {
+        if self.multiplier == 0 {
+            return;
+        }
+        let windows = self.timer.tick(delta).times_finished();
+        self.multiplier = self.multiplier.saturating_sub(windows);
+    }
+
+    pub fn reset(&mut self) {
+        self.multiplier = 0;
+    }
+}
+
+/// Scores kills through the combo multiplier.
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Combo>()
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                SystemSet::new()
+                    .with_run_criteria(playing_criteria)
+                    .with_system(combo_decay_system.label(ComboSystem::Decay))
+                    .with_system(
+                        score_system
+                            .label(ComboSystem::Score)
+                            .after(ComboSystem::Decay)
+                            .after(CollisionResponse),
+                    )
+                    .with_system(combo_break_system.after(ComboSystem::Score)),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::GameOver).with_system(combo_reset_system));
+    }
+}
+
+fn combo_decay_system(mut combo: ResMut<Combo>) {
+    combo.tick(Duration::from_secs_f32(TIME_STEP));
+}
+
+fn score_system(
+    mut player_state: ResMut<PlayerState>,
+    mut combo: ResMut<Combo>,
+    config: Res<GameConfig>,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+    mut awarded_events: EventWriter<ScoreAwarded>,
+) {
+    for event in destroyed_events.iter() {
+        let multiplier = combo.kill(&config.combo);
+        let points = event.score * multiplier as i64;
+        player_state.score += points;
+        awarded_events.send(ScoreAwarded {
+            points: event.score,
+            multiplier,
+            position: event.position,
+        });
+    }
+}
+
+/// Getting hit ends the chain.
+fn combo_break_system(mut combo: ResMut<Combo>, mut hit_events: EventReader<PlayerHit>) {
+    if hit_events.iter().count() > 0 {
+        combo.reset();
+    }
+}
+
+fn combo_reset_system(mut combo: ResMut<Combo>) {
+    combo.reset();
+}
@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use bevy::time::Timer;
 use bevy::math::Vec3;
-use bevy::prelude::Component;
+use bevy::prelude::{Color, Component, Entity};
 use bevy::math::Vec2;
+use serde::Deserialize;
 
 
 #[derive(Component)]
@@ -10,6 +13,7 @@ pub struct FpsText;
 #[derive(Component)]
 pub struct ScoreText;
 
+
 #[derive(Component)]
 pub struct Velocity {
     pub x: f32,
@@ -21,7 +25,8 @@ pub struct Movable {
     pub auto_despawn: bool,
 }
 
-#[derive(Component)]
+#[derive(Component, Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ShipStats {
     pub max_speed: f32,
     pub accel_speed: f32,
@@ -35,6 +40,10 @@ pub struct Explosion;
 #[derive(Component)]
 pub struct ExplosionToSpawn(pub Vec3);
 
+/// Size of an `ExplosionToSpawn` as a multiple of a normal one, if not 1.
+#[derive(Component)]
+pub struct ExplosionScale(pub f32);
+
 #[derive(Component)]
 pub struct ExplosionTimer(pub Timer);
 
@@ -47,18 +56,246 @@ impl Default for ExplosionTimer {
 #[derive(Component)]
 pub struct Player;
 
+/// Keeps a freshly respawned player from being hit, blinking until the timer runs out.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
 #[derive(Component)]
 pub struct FromPlayer;
 
 #[derive(Component)]
 pub struct Enemy;
 
+/// Lasers an enemy kind fires in one volley. Angles are in degrees, turning from straight
+/// down towards +x.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum FirePattern {
+    Silent,
+    /// `count` lasers side by side, straight down.
+    Straight { count: u32 },
+    /// One laser at the player's current position.
+    Aimed,
+    /// `count` lasers fanned evenly over `arc`, centered on straight down or, if `aimed`,
+    /// on the player.
+    Spread { count: u32, arc: f32, aimed: bool },
+    /// `count` lasers evenly around a full circle, which turns by `step` every volley.
+    Spiral { count: u32, step: f32 },
+}
+
+/// How an enemy kind moves once spawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum MovementStyle {
+    /// Flies its formation's path.
+    Orbit,
+    /// Flies the entry of its formation's path, then sweeps across the width of the screen.
+    Strafe,
+    /// Dives at the player and wraps back to the top after leaving the bottom.
+    Charge,
+}
+
+/// Per-kind stats of an enemy, copied from its `EnemyKindConfig` at spawn.
+#[derive(Component, Debug, Clone)]
+pub struct EnemyKind {
+    pub name: String,
+    pub hit_points: u32,
+    pub score: i64,
+    pub fire: FirePattern,
+    pub movement: MovementStyle,
+}
+
 #[derive(Component)]
 pub struct FromEnemy;
 
+/// An enemy's firing state: time until its next volley, volleys left in the current burst
+/// and how far its spiral has turned.
+#[derive(Component, Debug, Clone)]
+pub struct EnemyGun {
+    pub timer: Timer,
+    pub burst_left: u32,
+    pub spiral: f32,
+}
+
+/// A formation member that broke out to dive at the player. It homes in while above the
+/// player, turning at most `turn_rate` radians per second, and once it has wrapped from the
+/// bottom of the screen to the top it flies back to its place in the formation.
+#[derive(Component, Debug, Clone)]
+pub struct Diving {
+    pub direction: Vec2,
+    pub speed: f32,
+    pub turn_rate: f32,
+    pub rejoining: bool,
+}
+
+/// Controller of a grid of enemies marching like Space Invaders. Members hold a `GridMember`
+/// slot and are placed relative to `origin` every tick.
+#[derive(Component, Debug, Clone)]
+pub struct GridFormation {
+    pub origin: Vec2,
+    /// 1 when marching right, -1 when marching left.
+    pub direction: f32,
+    /// Marching speed with every member alive.
+    pub speed: f32,
+    /// Factor the speed reaches with one member left.
+    pub speed_up: f32,
+    pub step_down: f32,
+    /// Distance left to go down before marching sideways again.
+    pub step_left: f32,
+    /// Members the grid started with.
+    pub members: u32,
+}
+
+impl GridFormation {
+    /// Marching speed with `alive` members left.
+    pub fn current_speed(&self, alive: u32) -> f32 {
+        let lost = self.members.saturating_sub(alive) as f32 / (self.members.max(2) - 1) as f32;
+        self.speed * (1. + (self.speed_up - 1.) * lost.min(1.))
+    }
+}
+
+/// An enemy's slot in a `GridFormation`.
+#[derive(Component, Debug, Clone)]
+pub struct GridMember {
+    pub grid: Entity,
+    pub offset: Vec2,
+}
+
+/// A boss, the `bosses` entry `name`, in phase `phase` of it. It's shot through its
+/// `BossPart`s and carries the `Health` they share; `score` is copied from its config.
+#[derive(Component, Debug, Clone)]
+pub struct Boss {
+    pub name: String,
+    pub phase: usize,
+    pub score: i64,
+}
+
+/// One hitbox of a boss, kept `offset` from its center.
+#[derive(Component, Debug, Clone)]
+pub struct BossPart {
+    pub boss: Entity,
+    pub offset: Vec2,
+}
+
+/// What a pickup grants when the player collects it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PickupKind {
+    /// Fires the weapon one upgrade level higher.
+    SpreadShot,
+    /// Keeps firing while the fire key is held, at least as fast as `rapid_fire_interval`.
+    RapidFire,
+    /// Enemy lasers break on the ship without doing damage.
+    Shield,
+    ExtraLife,
+    SpeedBoost,
+}
+
+impl PickupKind {
+    /// Tint of the pickup sprite.
+    pub fn color(&self) -> Color {
+        match self {
+            PickupKind::SpreadShot => Color::ORANGE,
+            PickupKind::RapidFire => Color::YELLOW,
+            PickupKind::Shield => Color::CYAN,
+            PickupKind::ExtraLife => Color::GREEN,
+            PickupKind::SpeedBoost => Color::FUCHSIA,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Pickup(pub PickupKind);
+
+/// Timed pickup effects running on the player.
+#[derive(Component, Default)]
+pub struct PowerUps {
+    timers: Vec<(PickupKind, Timer)>,
+}
+
+impl PowerUps {
+    /// Starts `kind` for `secs`, or restarts it if already running.
+    pub fn grant(&mut self, kind: PickupKind, secs: f32) {
+        self.timers.retain(|(active, _)| *active != kind);
+        self.timers.push((kind, Timer::from_seconds(secs, false)));
+    }
+
+    pub fn active(&self, kind: PickupKind) -> bool {
+        self.timers.iter().any(|(active, _)| *active == kind)
+    }
+
+    /// Advances every effect, drops those that ran out and reports whether any did.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        for (_, timer) in self.timers.iter_mut() {
+            timer.tick(delta);
+        }
+        let running = self.timers.len();
+        self.timers.retain(|(_, timer)| !timer.finished());
+        self.timers.len() != running
+    }
+}
+
+/// The player's gun: the `weapons` entry it fires, its upgrade level and the time until
+/// it can fire again.
+#[derive(Component, Debug, Clone)]
+pub struct Weapon {
+    pub name: String,
+    /// Index into the weapon's `levels`, capped at the last one.
+    pub level: usize,
+    pub cooldown: Timer,
+}
+
+impl Weapon {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            level: 0,
+            cooldown: Timer::from_seconds(0., false),
+        }
+    }
+
+    /// Swaps to another weapon, starting over at its first level.
+    pub fn equip(&mut self, name: impl Into<String>) {
+        *self = Self::new(name);
+    }
+
+    pub fn upgrade(&mut self) {
+        self.level += 1;
+    }
+}
+
 #[derive(Component)]
 pub struct Laser;
 
+#[derive(Component)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Health { current: max, max }
+    }
+
+    /// Applies `damage` and reports whether this took the last hit point.
+    pub fn hit(&mut self, damage: u32) -> bool {
+        self.current = self.current.saturating_sub(damage);
+        self.current == 0
+    }
+}
+
+/// Hit points taken from whatever a laser hits.
+#[derive(Component)]
+pub struct Damage(pub u32);
+
+/// Briefly fades a sprite that took a hit without dying.
+#[derive(Component)]
+pub struct HitFlash(pub Timer);
+
+impl Default for HitFlash {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.1, false))
+    }
+}
+
 #[derive(Component)]
 pub struct SpriteSize(pub Vec2);
 
@@ -67,3 +304,105 @@ impl From<(f32, f32)> for SpriteSize {
         SpriteSize(Vec2::new(val.0, val.1))
     }
 }
+
+/// Collision layer bits, for `Collider::layer` and `Collider::mask`.
+pub mod layers {
+    pub const PLAYER: u32 = 1 << 0;
+    pub const ENEMY: u32 = 1 << 1;
+    pub const PLAYER_LASER: u32 = 1 << 2;
+    pub const ENEMY_LASER: u32 = 1 << 3;
+    pub const PICKUP: u32 = 1 << 4;
+}
+
+/// Shape in sprite pixels, scaled with the entity's `Transform`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColliderShape {
+    Aabb(Vec2),
+    Circle(f32),
+}
+
+/// Something the collision system reports pairs for. Two colliders are tested when either
+/// one's `mask` includes the other's `layer`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    /// Pixels trimmed off each side, for a hitbox tighter than the sprite.
+    pub inset: f32,
+    pub layer: u32,
+    pub mask: u32,
+}
+
+impl Collider {
+    pub fn aabb(size: (f32, f32), layer: u32, mask: u32) -> Self {
+        Collider { shape: ColliderShape::Aabb(Vec2::new(size.0, size.1)), inset: 0., layer, mask }
+    }
+
+    /// The box around a `size` sprite turned by `angle` radians, since colliders don't turn
+    /// with their `Transform`.
+    pub fn rotated_aabb(size: (f32, f32), angle: f32, layer: u32, mask: u32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        let (sin, cos) = (sin.abs(), cos.abs());
+        Collider::aabb((size.0 * cos + size.1 * sin, size.0 * sin + size.1 * cos), layer, mask)
+    }
+
+    pub fn circle(radius: f32, layer: u32, mask: u32) -> Self {
+        Collider { shape: ColliderShape::Circle(radius), inset: 0., layer, mask }
+    }
+
+    pub fn with_inset(self, inset: f32) -> Self {
+        Collider { inset, ..self }
+    }
+
+    pub fn interacts_with(&self, other: &Collider) -> bool {
+        self.mask & other.layer != 0 || other.mask & self.layer != 0
+    }
+}
+
+#[derive(Component)]
+pub struct MenuScreen;
+
+/// Simulated translation at the previous and current fixed tick.
+#[derive(Component)]
+pub struct Interpolated {
+    pub previous: Vec3,
+    pub current: Vec3,
+}
+
+impl From<Vec3> for Interpolated {
+    fn from(translation: Vec3) -> Self {
+        Interpolated { previous: translation, current: translation }
+    }
+}
+
+#[derive(Component)]
+pub struct WaveBanner;
+
+/// Shown during a run only: the status bar and everything in it.
+#[derive(Component)]
+pub struct StatusHud;
+
+#[derive(Component)]
+pub struct WaveText;
+
+#[derive(Component)]
+pub struct BestScoreText;
+
+/// Score popup drifting up from a kill, despawned when the timer runs out.
+#[derive(Component)]
+pub struct FloatingText(pub Timer);
+
+/// Row holding one pip per hull point.
+#[derive(Component)]
+pub struct HealthPips;
+
+/// Row holding one ship icon per life left.
+#[derive(Component)]
+pub struct LifeIcons;
+
+/// Health bar shown while a boss is up: the frame and the fill both carry it.
+#[derive(Component)]
+pub struct BossBar;
+
+/// The part of the boss health bar that shrinks as it loses hit points.
+#[derive(Component)]
+pub struct BossBarFill;
@@ -0,0 +1,1013 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use std::collections::BTreeMap;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::components::{EnemyKind, FirePattern, MovementStyle, PickupKind, ShipStats};
+use crate::path::{PathEnd, PathShape};
+
+/// Default location of the tuning file, relative to the working directory.
+pub const CONFIG_PATH: &str = "assets/config.ron";
+
+/// Gameplay tuning loaded from `assets/config.ron` at startup, and reloaded as an asset
+/// while the game runs when `ConfigReloadPlugin` is added.
+#[derive(Debug, Clone, Deserialize, TypeUuid)]
+#[uuid = "200bbffa-4c87-4fe5-808e-3b44cd1ac83a"]
+#[serde(deny_unknown_fields)]
+pub struct GameConfig {
+    pub player_respawn_delay: f64,
+    pub base_speed: f32,
+    pub sprite_scale: f32,
+    pub player: PlayerConfig,
+    /// Player weapons by name, as referenced from `player.weapon`.
+    pub weapons: BTreeMap<String, WeaponConfig>,
+    /// Enemy kinds by name, as referenced from `waves`.
+    pub enemies: BTreeMap<String, EnemyKindConfig>,
+    /// Bosses by name, as referenced from `waves`.
+    pub bosses: BTreeMap<String, BossConfig>,
+    /// Enemy paths by name, as referenced from `waves` and `bosses`.
+    pub paths: BTreeMap<String, PathConfig>,
+    pub explosion: ExplosionConfig,
+    pub sounds: SoundConfig,
+    pub waves: Vec<WaveConfig>,
+    pub escalation: EscalationConfig,
+    pub combo: ComboConfig,
+    pub pickups: PickupConfig,
+    pub dive: DiveConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub sprite: String,
+    pub size: (f32, f32),
+    pub laser_sprite: String,
+    pub laser_size: (f32, f32),
+    pub laser_damage: u32,
+    /// Ships per run.
+    pub lives: u32,
+    /// Most ships extra lives can bring the player up to.
+    pub max_lives: u32,
+    /// Hull points of each ship.
+    pub health: u32,
+    /// Seconds during which a respawned ship can't be hit.
+    pub invulnerability: f32,
+    /// Weapon each ship starts with.
+    pub weapon: String,
+    pub stats: ShipStats,
+}
+
+/// A player weapon. Upgrades step through `levels`; firing past the last one keeps it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponConfig {
+    /// Keeps firing while the fire key is held, rather than once per press.
+    pub autofire: bool,
+    pub levels: Vec<WeaponLevel>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WeaponLevel {
+    /// Seconds between two volleys.
+    pub fire_interval: f32,
+    /// Lasers fired together in one volley.
+    pub shots: Vec<ShotConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShotConfig {
+    /// Spawn point relative to the ship's center.
+    pub offset: (f32, f32),
+    /// Degrees off straight up, clockwise.
+    pub angle: f32,
+    /// Multiplier of the ship's `laser_speed`.
+    pub speed: f32,
+}
+
+impl WeaponConfig {
+    /// Level `level`, or the last one if the weapon doesn't go that high.
+    pub fn level(&self, level: usize) -> &WeaponLevel {
+        &self.levels[level.min(self.levels.len() - 1)]
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemyKindConfig {
+    pub sprite: String,
+    pub size: (f32, f32),
+    /// Multiplier of `sprite_scale`.
+    pub scale: f32,
+    /// Tint applied to the sprite, as RGB.
+    pub color: (f32, f32, f32),
+    pub laser_sprite: String,
+    pub laser_size: (f32, f32),
+    pub laser_damage: u32,
+    pub hit_points: u32,
+    pub score: i64,
+    pub fire: FireConfig,
+    pub movement: MovementStyle,
+    /// Multiplier of the wave's formation speed.
+    pub speed: f32,
+}
+
+/// How often an enemy kind fires. Each enemy keeps its own timer, started at a random
+/// point so that enemies don't fire in step.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FireConfig {
+    pub pattern: FirePattern,
+    /// Seconds between bursts, before the wave's `fire_rate` applies.
+    pub interval: f32,
+    /// Volleys per burst.
+    pub burst: u32,
+    /// Seconds between the volleys of a burst.
+    pub burst_interval: f32,
+}
+
+impl EnemyKindConfig {
+    pub fn kind(&self, name: &str) -> EnemyKind {
+        EnemyKind {
+            name: name.to_string(),
+            hit_points: self.hit_points,
+            score: self.score,
+            fire: self.fire.pattern,
+            movement: self.movement,
+        }
+    }
+}
+
+/// A large enemy closing a wave. It has several hitboxes sharing its hit points, and
+/// changes path and fire pattern as those run low.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossConfig {
+    pub sprite: String,
+    pub size: (f32, f32),
+    /// Scales the hull and its `parts` together, on top of `sprite_scale`.
+    pub scale: f32,
+    /// RGB the hull is drawn in, and flashes back to after each hit.
+    pub color: (f32, f32, f32),
+    pub laser_sprite: String,
+    pub laser_size: (f32, f32),
+    pub laser_damage: u32,
+    /// Shared by every part; `phases` change as they run down.
+    pub hit_points: u32,
+    pub score: i64,
+    pub parts: Vec<BossPartConfig>,
+    /// In order of decreasing `health`.
+    pub phases: Vec<BossPhaseConfig>,
+    /// Explosions chained across the hull when it's destroyed.
+    pub explosions: u32,
+    /// Seconds between two explosions of the chain.
+    pub explosion_interval: f32,
+    /// Size of each explosion of the chain, as a multiple of a normal one.
+    pub explosion_scale: f32,
+}
+
+/// One hitbox of a boss, in sprite pixels like `size`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossPartConfig {
+    /// Center of the hitbox relative to the center of the sprite.
+    pub offset: (f32, f32),
+    pub size: (f32, f32),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BossPhaseConfig {
+    /// Fraction of hit points left at which the phase starts; 1 for the first phase.
+    pub health: f32,
+    /// Entry in `paths`, flown unmirrored.
+    pub path: String,
+    /// Flying speed along `path`, in units of `base_speed`.
+    pub speed: f32,
+    pub fire: FireConfig,
+}
+
+impl BossConfig {
+    /// Index of the phase for `health` hit points left.
+    pub fn phase(&self, health: u32) -> usize {
+        let fraction = health as f32 / self.hit_points as f32;
+        self.phases.iter().rposition(|phase| fraction <= phase.health).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExplosionConfig {
+    pub sheet: String,
+    pub tile_size: (f32, f32),
+    pub columns: usize,
+    pub rows: usize,
+}
+
+/// Sound effects played on gameplay events, relative to the asset folder. `None` is silent.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SoundConfig {
+    pub enemy_destroyed: Option<String>,
+    pub player_hit: Option<String>,
+    pub player_died: Option<String>,
+    pub laser_impact: Option<String>,
+}
+
+/// One wave of enemies. Waves past the end of the list repeat the last one, escalated.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WaveConfig {
+    /// Seconds between the previous wave being cleared and this one starting.
+    pub delay: f32,
+    pub enemy_count: u32,
+    /// Seconds between two enemies of the wave spawning.
+    pub spawn_interval: f32,
+    /// Multiplier of every enemy's rate of fire.
+    pub fire_rate: f32,
+    /// Enemy kinds to draw from, with their relative weights.
+    pub enemies: Vec<(String, u32)>,
+    pub formation: FormationConfig,
+    /// Spawns the wave as one marching grid instead of formations trickling in. Its
+    /// `enemy_count` is then the size of the grid.
+    pub grid: Option<GridConfig>,
+    /// Boss coming in once the wave's enemies are cleared; the wave ends when it's
+    /// destroyed.
+    pub boss: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FormationConfig {
+    /// Enemies sharing one formation template.
+    pub members: u32,
+    /// Speed along the path, or of the march for grids, in units of `base_speed`.
+    pub speed: f32,
+    /// Distance along the path between neighbouring members.
+    pub spacing: f32,
+    /// Score for destroying every member of a formation of two or more.
+    pub bonus: i64,
+    /// Seconds between two members breaking out to dive at the player, or 0 to never dive.
+    pub dive_interval: f32,
+    /// Paths to draw each formation's from, possibly mirrored.
+    pub paths: Vec<String>,
+}
+
+/// A Space Invaders grid: marches sideways at the wave's formation speed, steps down at the
+/// edges of the screen, and speeds up as its members die.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridConfig {
+    pub rows: u32,
+    pub columns: u32,
+    /// Distance between the centers of neighbouring members.
+    pub spacing: (f32, f32),
+    /// Distance the grid goes down at each edge.
+    pub step_down: f32,
+    /// Factor the marching speed reaches with one member left.
+    pub speed_up: f32,
+}
+
+/// A route for formations to fly: `entry` once, then whatever `then` says.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathConfig {
+    pub entry: PathShape,
+    pub then: PathEnd,
+}
+
+impl WaveConfig {
+    /// Draws one of the wave's enemy kinds according to their weights.
+    pub fn pick_enemy(&self, rng: &mut impl Rng) -> &str {
+        let weights = WeightedIndex::new(self.enemies.iter().map(|(_, weight)| *weight)).unwrap();
+        &self.enemies[weights.sample(rng)].0
+    }
+}
+
+/// Kills in quick succession raise a score multiplier.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ComboConfig {
+    /// Seconds after a kill for the next one to extend the chain. Each window that passes
+    /// without one drops the multiplier by one.
+    pub window: f32,
+    pub max_multiplier: u32,
+}
+
+/// Power-ups dropped by destroyed enemies.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PickupConfig {
+    /// Chance of a destroyed enemy dropping one.
+    pub drop_chance: f64,
+    pub size: (f32, f32),
+    /// Falling speed, as a multiple of `base_speed`.
+    pub speed: f32,
+    /// Seconds the timed effects last.
+    pub duration: f32,
+    /// Longest interval between volleys under rapid fire.
+    pub rapid_fire_interval: f32,
+    /// Multiplier of the ship's speed and acceleration under speed boost.
+    pub speed_boost: f32,
+    /// Pickup kinds to draw from, with their relative weights.
+    pub kinds: Vec<(PickupKind, u32)>,
+}
+
+impl PickupConfig {
+    /// Draws a pickup kind according to the weights.
+    pub fn pick(&self, rng: &mut impl Rng) -> PickupKind {
+        let weights = WeightedIndex::new(self.kinds.iter().map(|(_, weight)| *weight)).unwrap();
+        self.kinds[weights.sample(rng)].0
+    }
+}
+
+/// Formation members diving at the player.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DiveConfig {
+    /// Speed of divers, in units of `base_speed`.
+    pub speed: f32,
+    /// Fastest turn towards the player, in degrees per second.
+    pub turn_rate: f32,
+    /// Damage dealt by ramming the player, once per dive, and by chargers once per pass.
+    pub damage: u32,
+}
+
+/// Applied once per wave beyond the last defined one.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EscalationConfig {
+    /// Enemies added.
+    pub enemy_count: u32,
+    /// Factor applied to the fire rate.
+    pub fire_rate: f32,
+    /// Factor applied to the formation speed.
+    pub speed: f32,
+}
+
+impl ExplosionConfig {
+    /// Number of animation frames in the sheet.
+    pub fn len(&self) -> usize {
+        self.columns * self.rows
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(ron::Error),
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "could not read config: {err}"),
+            ConfigError::Parse(err) => write!(f, "could not parse config: {err}"),
+            ConfigError::Invalid(problems) => {
+                write!(f, "invalid config:")?;
+                for problem in problems {
+                    write!(f, "\n  - {problem}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    /// Definition of wave `number` (1-based), escalating past the last defined wave.
+    pub fn wave(&self, number: u32) -> WaveConfig {
+        let defined = self.waves.len() as u32;
+        let mut wave = self.waves[(number.max(1).min(defined) - 1) as usize].clone();
+        let extra = number.saturating_sub(defined);
+        if extra > 0 {
+            wave.enemy_count += self.escalation.enemy_count * extra;
+            wave.fire_rate *= self.escalation.fire_rate.powi(extra as i32);
+            wave.formation.speed *= self.escalation.speed.powi(extra as i32);
+        }
+        // Grids escalate by whole rows, enough to hold the extra enemies.
+        if let Some(grid) = wave.grid.as_mut() {
+            let added = self.escalation.enemy_count * extra;
+            grid.rows += (added + grid.columns - 1) / grid.columns;
+            wave.enemy_count = grid.rows * grid.columns;
+        }
+        wave
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = ron::from_str(text).map_err(ConfigError::Parse)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks every value and reports all problems at once rather than the first one.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+        let positive = |size: (f32, f32)| size.0 > 0. && size.1 > 0.;
+        let has_shots = |pattern: FirePattern| match pattern {
+            FirePattern::Straight { count } | FirePattern::Spread { count, .. } | FirePattern::Spiral { count, .. } => {
+                count > 0
+            }
+            FirePattern::Silent | FirePattern::Aimed => true,
+        };
+
+        check(self.player_respawn_delay >= 0., "player_respawn_delay must not be negative");
+        check(self.base_speed > 0., "base_speed must be positive");
+        check(self.sprite_scale > 0., "sprite_scale must be positive");
+
+        check(!self.player.sprite.is_empty(), "player.sprite must not be empty");
+        check(positive(self.player.size), "player.size must be positive");
+        check(!self.player.laser_sprite.is_empty(), "player.laser_sprite must not be empty");
+        check(positive(self.player.laser_size), "player.laser_size must be positive");
+        check(self.player.laser_damage > 0, "player.laser_damage must be at least 1");
+        check(self.player.lives > 0, "player.lives must be at least 1");
+        check(self.player.max_lives >= self.player.lives, "player.max_lives must be at least player.lives");
+        check(self.player.health > 0, "player.health must be at least 1");
+        check(self.player.invulnerability >= 0., "player.invulnerability must not be negative");
+        check(self.player.stats.max_speed > 0., "player.stats.max_speed must be positive");
+        check(self.player.stats.accel_speed > 0., "player.stats.accel_speed must be positive");
+        check(self.player.stats.decel_speed > 0., "player.stats.decel_speed must be positive");
+        check(self.player.stats.laser_speed > 0., "player.stats.laser_speed must be positive");
+        check(
+            self.weapons.contains_key(&self.player.weapon),
+            &format!("player.weapon refers to unknown weapon `{}`", self.player.weapon),
+        );
+
+        for (name, weapon) in &self.weapons {
+            let mut check_weapon = |ok: bool, problem: &str| check(ok, &format!("weapons.{name}.{problem}"));
+            check_weapon(!weapon.levels.is_empty(), "levels must define at least one level");
+            for (i, level) in weapon.levels.iter().enumerate() {
+                check_weapon(level.fire_interval > 0., &format!("levels[{i}].fire_interval must be positive"));
+                check_weapon(!level.shots.is_empty(), &format!("levels[{i}].shots must list at least one shot"));
+                for (j, shot) in level.shots.iter().enumerate() {
+                    check_weapon(shot.speed > 0., &format!("levels[{i}].shots[{j}].speed must be positive"));
+                }
+            }
+        }
+
+        check(!self.enemies.is_empty(), "enemies must define at least one kind");
+        for (name, kind) in &self.enemies {
+            let mut check_kind = |ok: bool, problem: &str| check(ok, &format!("enemies.{name}.{problem}"));
+            check_kind(!kind.sprite.is_empty(), "sprite must not be empty");
+            check_kind(positive(kind.size), "size must be positive");
+            check_kind(kind.scale > 0., "scale must be positive");
+            check_kind(!kind.laser_sprite.is_empty(), "laser_sprite must not be empty");
+            check_kind(positive(kind.laser_size), "laser_size must be positive");
+            check_kind(kind.laser_damage > 0, "laser_damage must be at least 1");
+            check_kind(kind.hit_points > 0, "hit_points must be at least 1");
+            check_kind(kind.score >= 0, "score must not be negative");
+            check_kind(kind.speed > 0., "speed must be positive");
+            check_kind(kind.fire.interval > 0., "fire.interval must be positive");
+            check_kind(kind.fire.burst > 0, "fire.burst must be at least 1");
+            check_kind(kind.fire.burst_interval >= 0., "fire.burst_interval must not be negative");
+            check_kind(has_shots(kind.fire.pattern), "fire.pattern count must be at least 1");
+        }
+
+        for (name, boss) in &self.bosses {
+            let mut check_boss = |ok: bool, problem: &str| check(ok, &format!("bosses.{name}.{problem}"));
+            check_boss(!self.enemies.contains_key(name), "shares its name with an enemy kind");
+            check_boss(!boss.sprite.is_empty(), "sprite must not be empty");
+            check_boss(positive(boss.size), "size must be positive");
+            check_boss(boss.scale > 0., "scale must be positive");
+            check_boss(!boss.laser_sprite.is_empty(), "laser_sprite must not be empty");
+            check_boss(positive(boss.laser_size), "laser_size must be positive");
+            check_boss(boss.laser_damage > 0, "laser_damage must be at least 1");
+            check_boss(boss.hit_points > 0, "hit_points must be at least 1");
+            check_boss(boss.score >= 0, "score must not be negative");
+            check_boss(!boss.parts.is_empty(), "parts must list at least one hitbox");
+            for (i, part) in boss.parts.iter().enumerate() {
+                check_boss(positive(part.size), &format!("parts[{i}].size must be positive"));
+            }
+            check_boss(!boss.phases.is_empty(), "phases must list at least one phase");
+            check_boss(boss.phases.first().map_or(true, |phase| phase.health == 1.), "phases[0].health must be 1");
+            for (i, phase) in boss.phases.iter().enumerate() {
+                let previous = if i > 0 { boss.phases[i - 1].health } else { 1. };
+                check_boss(
+                    phase.health > 0. && phase.health <= previous,
+                    &format!("phases[{i}].health must be positive and no more than the phase before"),
+                );
+                check_boss(
+                    self.paths.contains_key(&phase.path),
+                    &format!("phases[{i}].path refers to unknown path `{}`", phase.path),
+                );
+                check_boss(phase.speed > 0., &format!("phases[{i}].speed must be positive"));
+                check_boss(phase.fire.interval > 0., &format!("phases[{i}].fire.interval must be positive"));
+                check_boss(phase.fire.burst > 0, &format!("phases[{i}].fire.burst must be at least 1"));
+                check_boss(
+                    phase.fire.burst_interval >= 0.,
+                    &format!("phases[{i}].fire.burst_interval must not be negative"),
+                );
+                check_boss(has_shots(phase.fire.pattern), &format!("phases[{i}].fire.pattern count must be at least 1"));
+            }
+            check_boss(boss.explosion_interval > 0., "explosion_interval must be positive");
+            check_boss(boss.explosion_scale > 0., "explosion_scale must be positive");
+        }
+
+        for (name, path) in &self.paths {
+            if let Some(problem) = path.entry.problem() {
+                check(false, &format!("paths.{name}.entry {problem}"));
+            }
+            if let PathEnd::Loop(shape) = &path.then {
+                if let Some(problem) = shape.problem() {
+                    check(false, &format!("paths.{name}.then {problem}"));
+                }
+            }
+        }
+
+        check(!self.explosion.sheet.is_empty(), "explosion.sheet must not be empty");
+        check(positive(self.explosion.tile_size), "explosion.tile_size must be positive");
+        check(self.explosion.len() > 0, "explosion.columns and explosion.rows must be at least 1");
+
+        let sounds = [
+            ("enemy_destroyed", &self.sounds.enemy_destroyed),
+            ("player_hit", &self.sounds.player_hit),
+            ("player_died", &self.sounds.player_died),
+            ("laser_impact", &self.sounds.laser_impact),
+        ];
+        for (name, path) in sounds {
+            check(path.as_ref().map_or(true, |path| !path.is_empty()), &format!("sounds.{name} must not be empty"));
+        }
+
+        check(!self.waves.is_empty(), "waves must define at least one wave");
+        for (i, wave) in self.waves.iter().enumerate() {
+            let mut check_wave = |ok: bool, problem: &str| check(ok, &format!("waves[{i}].{problem}"));
+            check_wave(wave.delay >= 0., "delay must not be negative");
+            check_wave(wave.enemy_count > 0, "enemy_count must be at least 1");
+            check_wave(wave.spawn_interval > 0., "spawn_interval must be positive");
+            check_wave(wave.fire_rate >= 0., "fire_rate must not be negative");
+            check_wave(!wave.enemies.is_empty(), "enemies must list at least one kind");
+            check_wave(wave.enemies.iter().any(|(_, weight)| *weight > 0), "enemies need a positive weight");
+            for (name, _) in &wave.enemies {
+                check_wave(self.enemies.contains_key(name), &format!("enemies refers to unknown kind `{name}`"));
+            }
+            check_wave(wave.formation.members > 0, "formation.members must be at least 1");
+            check_wave(wave.formation.speed > 0., "formation.speed must be positive");
+            check_wave(wave.formation.spacing >= 0., "formation.spacing must not be negative");
+            check_wave(wave.formation.bonus >= 0, "formation.bonus must not be negative");
+            check_wave(wave.formation.dive_interval >= 0., "formation.dive_interval must not be negative");
+            check_wave(!wave.formation.paths.is_empty(), "formation.paths must list at least one path");
+            for name in &wave.formation.paths {
+                check_wave(self.paths.contains_key(name), &format!("formation.paths refers to unknown path `{name}`"));
+            }
+            if let Some(grid) = &wave.grid {
+                check_wave(grid.rows > 0 && grid.columns > 0, "grid.rows and grid.columns must be at least 1");
+                check_wave(positive(grid.spacing), "grid.spacing must be positive");
+                check_wave(grid.step_down >= 0., "grid.step_down must not be negative");
+                check_wave(grid.speed_up >= 1., "grid.speed_up must be at least 1");
+            }
+            if let Some(name) = &wave.boss {
+                check_wave(self.bosses.contains_key(name), &format!("boss refers to unknown boss `{name}`"));
+            }
+        }
+        check(self.escalation.fire_rate > 0., "escalation.fire_rate must be positive");
+        check(self.escalation.speed > 0., "escalation.speed must be positive");
+        check(self.combo.window > 0., "combo.window must be positive");
+        check(self.combo.max_multiplier > 0, "combo.max_multiplier must be at least 1");
+        check((0. ..=1.).contains(&self.pickups.drop_chance), "pickups.drop_chance must be between 0 and 1");
+        check(positive(self.pickups.size), "pickups.size must be positive");
+        check(self.pickups.speed > 0., "pickups.speed must be positive");
+        check(self.pickups.duration > 0., "pickups.duration must be positive");
+        check(self.pickups.rapid_fire_interval > 0., "pickups.rapid_fire_interval must be positive");
+        check(self.pickups.speed_boost > 0., "pickups.speed_boost must be positive");
+        check(self.pickups.kinds.iter().any(|(_, weight)| *weight > 0), "pickups.kinds need a positive weight");
+        check(self.dive.speed > 0., "dive.speed must be positive");
+        check(self.dive.turn_rate >= 0., "dive.turn_rate must not be negative");
+        check(self.dive.damage > 0, "dive.damage must be at least 1");
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+/// Mirrors `assets/config.ron`, so headless runs and tests don't need the file.
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            player_respawn_delay: 2.0,
+            base_speed: 500.,
+            sprite_scale: 0.5,
+            player: PlayerConfig {
+                sprite: "player_a_01.png".to_string(),
+                size: (144., 75.),
+                laser_sprite: "laser_a_01.png".to_string(),
+                laser_size: (9., 54.),
+                laser_damage: 1,
+                lives: 3,
+                max_lives: 5,
+                health: 3,
+                invulnerability: 2.0,
+                weapon: "twin".to_string(),
+                stats: ShipStats { accel_speed: 0.3, decel_speed: 10.0, max_speed: 0.7, laser_speed: 1.0 },
+            },
+            weapons: BTreeMap::from([
+                ("twin".to_string(), WeaponConfig {
+                    autofire: false,
+                    levels: vec![
+                        WeaponLevel {
+                            fire_interval: 0.2,
+                            shots: vec![shot((31., 15.), 0., 1.), shot((-31., 15.), 0., 1.)],
+                        },
+                        WeaponLevel {
+                            fire_interval: 0.2,
+                            shots: vec![
+                                shot((31., 15.), 0., 1.),
+                                shot((-31., 15.), 0., 1.),
+                                shot((0., 15.), 14., 1.),
+                                shot((0., 15.), -14., 1.),
+                            ],
+                        },
+                        WeaponLevel {
+                            fire_interval: 0.15,
+                            shots: vec![
+                                shot((31., 15.), 0., 1.),
+                                shot((-31., 15.), 0., 1.),
+                                shot((0., 15.), 14., 1.),
+                                shot((0., 15.), -14., 1.),
+                                shot((0., 15.), 28., 1.),
+                                shot((0., 15.), -28., 1.),
+                            ],
+                        },
+                    ],
+                }),
+                ("pulse".to_string(), WeaponConfig {
+                    autofire: true,
+                    levels: vec![
+                        WeaponLevel { fire_interval: 0.12, shots: vec![shot((0., 20.), 0., 1.5)] },
+                        WeaponLevel {
+                            fire_interval: 0.1,
+                            shots: vec![shot((12., 20.), 0., 1.5), shot((-12., 20.), 0., 1.5)],
+                        },
+                    ],
+                }),
+            ]),
+            enemies: BTreeMap::from([
+                ("scout".to_string(), EnemyKindConfig {
+                    sprite: "enemy_a_01.png".to_string(),
+                    size: (144., 75.),
+                    scale: 0.7,
+                    color: (0.6, 0.8, 1.0),
+                    laser_sprite: "laser_b_01.png".to_string(),
+                    laser_size: (17., 55.),
+                    laser_damage: 1,
+                    hit_points: 1,
+                    score: 1,
+                    fire: FireConfig {
+                        pattern: FirePattern::Aimed,
+                        interval: 3.0,
+                        burst: 1,
+                        burst_interval: 0.,
+                    },
+                    movement: MovementStyle::Orbit,
+                    speed: 1.3,
+                }),
+                ("gunner".to_string(), EnemyKindConfig {
+                    sprite: "enemy_a_01.png".to_string(),
+                    size: (144., 75.),
+                    scale: 1.0,
+                    color: (1.0, 1.0, 1.0),
+                    laser_sprite: "laser_b_01.png".to_string(),
+                    laser_size: (17., 55.),
+                    laser_damage: 1,
+                    hit_points: 2,
+                    score: 3,
+                    fire: FireConfig {
+                        pattern: FirePattern::Spread { count: 3, arc: 30., aimed: true },
+                        interval: 2.5,
+                        burst: 2,
+                        burst_interval: 0.2,
+                    },
+                    movement: MovementStyle::Orbit,
+                    speed: 1.0,
+                }),
+                ("tank".to_string(), EnemyKindConfig {
+                    sprite: "enemy_a_01.png".to_string(),
+                    size: (144., 75.),
+                    scale: 1.4,
+                    color: (1.0, 0.5, 0.5),
+                    laser_sprite: "laser_b_01.png".to_string(),
+                    laser_size: (17., 55.),
+                    laser_damage: 1,
+                    hit_points: 5,
+                    score: 5,
+                    fire: FireConfig {
+                        pattern: FirePattern::Spiral { count: 6, step: 20. },
+                        interval: 3.5,
+                        burst: 3,
+                        burst_interval: 0.25,
+                    },
+                    movement: MovementStyle::Strafe,
+                    speed: 0.5,
+                }),
+                ("kamikaze".to_string(), EnemyKindConfig {
+                    sprite: "enemy_a_01.png".to_string(),
+                    size: (144., 75.),
+                    scale: 0.8,
+                    color: (1.0, 0.8, 0.2),
+                    laser_sprite: "laser_b_01.png".to_string(),
+                    laser_size: (17., 55.),
+                    laser_damage: 1,
+                    hit_points: 1,
+                    score: 2,
+                    fire: FireConfig {
+                        pattern: FirePattern::Silent,
+                        interval: 1.,
+                        burst: 1,
+                        burst_interval: 0.,
+                    },
+                    movement: MovementStyle::Charge,
+                    speed: 0.6,
+                }),
+            ]),
+            bosses: BTreeMap::from([
+                ("mothership".to_string(), BossConfig {
+                    sprite: "enemy_a_01.png".to_string(),
+                    size: (144., 75.),
+                    scale: 2.2,
+                    color: (0.8, 0.5, 1.0),
+                    laser_sprite: "laser_b_01.png".to_string(),
+                    laser_size: (17., 55.),
+                    laser_damage: 1,
+                    hit_points: 80,
+                    score: 50,
+                    parts: vec![
+                        BossPartConfig { offset: (0., 0.), size: (56., 75.) },
+                        BossPartConfig { offset: (-46., 8.), size: (40., 40.) },
+                        BossPartConfig { offset: (46., 8.), size: (40., 40.) },
+                    ],
+                    phases: vec![
+                        BossPhaseConfig {
+                            health: 1.,
+                            path: "hover".to_string(),
+                            speed: 0.15,
+                            fire: FireConfig {
+                                pattern: FirePattern::Spread { count: 5, arc: 60., aimed: true },
+                                interval: 2.0,
+                                burst: 2,
+                                burst_interval: 0.3,
+                            },
+                        },
+                        BossPhaseConfig {
+                            health: 0.6,
+                            path: "sweep".to_string(),
+                            speed: 0.25,
+                            fire: FireConfig {
+                                pattern: FirePattern::Spiral { count: 8, step: 15. },
+                                interval: 1.2,
+                                burst: 3,
+                                burst_interval: 0.2,
+                            },
+                        },
+                        BossPhaseConfig {
+                            health: 0.3,
+                            path: "sweep".to_string(),
+                            speed: 0.4,
+                            fire: FireConfig {
+                                pattern: FirePattern::Aimed,
+                                interval: 0.6,
+                                burst: 3,
+                                burst_interval: 0.1,
+                            },
+                        },
+                    ],
+                    explosions: 12,
+                    explosion_interval: 0.12,
+                    explosion_scale: 2.5,
+                }),
+            ]),
+            paths: BTreeMap::from([
+                ("orbit".to_string(), PathConfig {
+                    entry: PathShape::Waypoints(vec![(-1.3, 0.6), (-0.4, 0.5)]),
+                    then: PathEnd::Loop(PathShape::Ellipse { center: (-0.1, 0.45), radius: (0.35, 0.3) }),
+                }),
+                ("swoop".to_string(), PathConfig {
+                    entry: PathShape::CatmullRom(vec![
+                        (-1.3, 1.1),
+                        (-0.6, 0.6),
+                        (0.0, -0.2),
+                        (0.5, 0.0),
+                        (0.3, 0.5),
+                        (-0.2, 0.6),
+                    ]),
+                    then: PathEnd::Hold,
+                }),
+                ("dive".to_string(), PathConfig {
+                    entry: PathShape::Bezier(vec![(1.3, 0.8), (0.5, -0.8), (-0.5, -0.8), (-0.4, 0.5)]),
+                    then: PathEnd::Loop(PathShape::Waypoints(vec![(-0.4, 0.5), (0.4, 0.5)])),
+                }),
+                ("hover".to_string(), PathConfig {
+                    entry: PathShape::Waypoints(vec![(0.0, 1.4), (0.0, 0.55)]),
+                    then: PathEnd::Loop(PathShape::Ellipse { center: (0.0, 0.55), radius: (0.5, 0.08) }),
+                }),
+                ("sweep".to_string(), PathConfig {
+                    entry: PathShape::Waypoints(vec![(0.0, 0.55)]),
+                    then: PathEnd::Loop(PathShape::Waypoints(vec![(-0.6, 0.6), (0.6, 0.2), (0.6, 0.6), (-0.6, 0.2)])),
+                }),
+            ]),
+            explosion: ExplosionConfig {
+                sheet: "explo_a_sheet.png".to_string(),
+                tile_size: (64., 64.),
+                columns: 4,
+                rows: 4,
+            },
+            sounds: SoundConfig::default(),
+            waves: vec![
+                WaveConfig {
+                    delay: 2.0,
+                    enemy_count: 4,
+                    spawn_interval: 1.0,
+                    fire_rate: 1.0,
+                    enemies: vec![("scout".to_string(), 3), ("gunner".to_string(), 1)],
+                    formation: FormationConfig {
+                        members: 2,
+                        speed: 1.0,
+                        spacing: 70.,
+                        bonus: 5,
+                        dive_interval: 0.,
+                        paths: vec!["orbit".to_string()],
+                    },
+                    grid: None,
+                    boss: None,
+                },
+                WaveConfig {
+                    delay: 3.0,
+                    enemy_count: 6,
+                    spawn_interval: 0.8,
+                    fire_rate: 1.2,
+                    enemies: vec![
+                        ("scout".to_string(), 2),
+                        ("gunner".to_string(), 2),
+                        ("kamikaze".to_string(), 1),
+                    ],
+                    formation: FormationConfig {
+                        members: 3,
+                        speed: 1.1,
+                        spacing: 70.,
+                        bonus: 10,
+                        dive_interval: 6.,
+                        paths: vec!["orbit".to_string(), "swoop".to_string()],
+                    },
+                    grid: None,
+                    boss: None,
+                },
+                WaveConfig {
+                    delay: 3.0,
+                    enemy_count: 8,
+                    spawn_interval: 0.6,
+                    fire_rate: 1.4,
+                    enemies: vec![
+                        ("scout".to_string(), 2),
+                        ("gunner".to_string(), 2),
+                        ("tank".to_string(), 1),
+                        ("kamikaze".to_string(), 1),
+                    ],
+                    formation: FormationConfig {
+                        members: 4,
+                        speed: 1.2,
+                        spacing: 70.,
+                        bonus: 20,
+                        dive_interval: 4.,
+                        paths: vec!["swoop".to_string(), "dive".to_string(), "orbit".to_string()],
+                    },
+                    grid: None,
+                    boss: Some("mothership".to_string()),
+                },
+                WaveConfig {
+                    delay: 3.0,
+                    enemy_count: 24,
+                    spawn_interval: 1.0,
+                    fire_rate: 0.5,
+                    enemies: vec![("scout".to_string(), 2), ("gunner".to_string(), 1)],
+                    formation: FormationConfig {
+                        members: 1,
+                        speed: 0.1,
+                        spacing: 0.,
+                        bonus: 0,
+                        dive_interval: 0.,
+                        paths: vec!["orbit".to_string()],
+                    },
+                    grid: Some(GridConfig {
+                        rows: 4,
+                        columns: 6,
+                        spacing: (80., 50.),
+                        step_down: 25.,
+                        speed_up: 5.,
+                    }),
+                    boss: None,
+                },
+            ],
+            escalation: EscalationConfig { enemy_count: 2, fire_rate: 1.15, speed: 1.05 },
+            combo: ComboConfig { window: 1.5, max_multiplier: 8 },
+            pickups: PickupConfig {
+                drop_chance: 0.1,
+                size: (24., 24.),
+                speed: 0.25,
+                duration: 8.,
+                rapid_fire_interval: 0.15,
+                speed_boost: 1.5,
+                kinds: vec![
+                    (PickupKind::SpreadShot, 3),
+                    (PickupKind::RapidFire, 3),
+                    (PickupKind::Shield, 2),
+                    (PickupKind::ExtraLife, 1),
+                    (PickupKind::SpeedBoost, 3),
+                ],
+            },
+            dive: DiveConfig { speed: 0.8, turn_rate: 90., damage: 1 },
+        }
+    }
+}
+
+fn shot(offset: (f32, f32), angle: f32, speed: f32) -> ShotConfig {
+    ShotConfig { offset, angle, speed }
+}
+
+/// Parses and validates `.ron` tuning files; invalid edits are logged and leave the
+/// previous config in place.
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let config = GameConfig::parse(std::str::from_utf8(bytes)?)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Watches the tuning file through the `AssetServer` and copies every modified version
+/// that validates into the `GameConfig` resource. Needs `AssetPlugin`; enable
+/// `AssetServerSettings::watch_for_changes` for edits to be picked up.
+pub struct ConfigReloadPlugin {
+    /// Path relative to the asset folder, e.g. `config.ron`.
+    pub asset_path: String,
+}
+
+struct GameConfigHandle(Handle<GameConfig>);
+
+impl Plugin for ConfigReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<GameConfig>().init_asset_loader::<GameConfigLoader>();
+
+        let handle = app.world.resource::<AssetServer>().load(self.asset_path.as_str());
+        app.insert_resource(GameConfigHandle(handle)).add_system(config_reload_system);
+    }
+}
+
+fn config_reload_system(
+    mut events: EventReader<AssetEvent<GameConfig>>,
+    assets: Res<Assets<GameConfig>>,
+    handle: Res<GameConfigHandle>,
+    mut config: ResMut<GameConfig>,
+) {
+    for event in events.iter() {
+        if let AssetEvent::Modified { handle: modified } = event {
+            if *modified == handle.0 {
+                if let Some(reloaded) = assets.get(modified) {
+                    match reloaded.validate() {
+                        Ok(()) => {
+                            info!("reloaded game config");
+                            *config = reloaded.clone();
+                        }
+                        Err(err) => warn!("kept the previous game config: {err}"),
+                    }
+                }
+            }
+        }
+    }
+}
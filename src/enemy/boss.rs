@@ -0,0 +1,273 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{reload, spawn_volley, Ammo};
+use crate::components::{
+    layers, Boss, BossPart, Collider, EnemyGun, ExplosionScale, ExplosionToSpawn, Health, Interpolated, Player,
+    SpriteSize,
+};
+use crate::config::{BossConfig, GameConfig};
+use crate::events::EnemyDestroyed;
+use crate::path::Path;
+use crate::wave::CurrentWave;
+use crate::{EnemyCount, GameTextures, WinSize, TIME_STEP};
+
+/// How much faster than its path a boss may fly to catch up with it, e.g. after changing
+/// phase.
+const CATCH_UP: f32 = 2.;
+/// Turn between two explosions of a wreck, in radians, so that they spiral out over it.
+const WRECK_TURN: f32 = 2.4;
+
+/// The path a boss flies in its current phase, how far along it is, and how fast it goes.
+#[derive(Component)]
+pub struct BossFlight {
+    path: Path,
+    distance: f32,
+    speed: f32,
+}
+
+/// A destroyed boss still going up: explosions `scale` times the normal size go off across
+/// `size` around `center`, one every tick of `timer`, until `left` runs out.
+#[derive(Component)]
+pub struct BossWreck {
+    center: Vec3,
+    size: Vec2,
+    scale: f32,
+    timer: Timer,
+    count: u32,
+    left: u32,
+}
+
+/// The path of the boss's `phase`, or None if a config reload removed it.
+fn phase_path(boss: &BossConfig, phase: usize, config: &GameConfig, win_size: &WinSize) -> Option<Path> {
+    let name = &boss.phases[phase].path;
+    match config.paths.get(name) {
+        Some(path) => Some(Path::new(&path.entry, &path.then, Vec2::new(win_size.w / 2., win_size.h / 2.), false)),
+        None => {
+            warn!("path `{name}` is no longer in the config");
+            None
+        }
+    }
+}
+
+fn phase_gun(boss: &BossConfig, phase: usize) -> EnemyGun {
+    let fire = &boss.phases[phase].fire;
+    EnemyGun { timer: Timer::from_seconds(fire.interval, false), burst_left: fire.burst, spiral: 0. }
+}
+
+/// Brings in the wave's boss, with its hitboxes, once the rest of the wave is cleared.
+pub fn boss_spawn_system(
+    mut commands: Commands,
+    mut wave: ResMut<CurrentWave>,
+    mut enemy_count: ResMut<EnemyCount>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    win_size: Res<WinSize>,
+) {
+    if !wave.boss_due() {
+        return;
+    }
+    let name = match &wave.config.boss {
+        Some(name) => name.clone(),
+        None => return,
+    };
+
+    let boss = match config.bosses.get(&name) {
+        Some(boss) => boss,
+        None => {
+            warn!("boss `{name}` is no longer in the config, skipping it");
+            return;
+        }
+    };
+    let path = match phase_path(boss, 0, &config, &win_size) {
+        Some(path) => path,
+        None => return,
+    };
+    let speed = config.base_speed * boss.phases[0].speed;
+    let start = path.start();
+    let translation = start.extend(10.);
+    let scale = config.sprite_scale * boss.scale;
+    let (r, g, b) = boss.color;
+    let id = commands
+        .spawn_bundle(SpriteBundle {
+            texture: game_textures.enemies.get(&name).cloned().unwrap_or_default(),
+            sprite: Sprite {
+                color: Color::rgb(r, g, b),
+                ..Default::default()
+            },
+            transform: Transform {
+                translation,
+                scale: Vec3::new(scale, scale, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Boss { name: name.clone(), phase: 0, score: boss.score })
+        .insert(BossFlight { path, distance: 0., speed })
+        .insert(Health::new(boss.hit_points))
+        .insert(phase_gun(boss, 0))
+        .insert(Interpolated::from(translation))
+        .insert(SpriteSize::from(boss.size))
+        .id();
+
+    for part in &boss.parts {
+        let offset = Vec2::from(part.offset) * scale;
+        commands
+            .spawn_bundle(TransformBundle::from_transform(Transform {
+                translation: (start + offset).extend(10.),
+                scale: Vec3::new(scale, scale, 1.0),
+                ..Default::default()
+            }))
+            .insert(BossPart { boss: id, offset })
+            .insert(Collider::aabb(part.size, layers::ENEMY, layers::PLAYER_LASER));
+    }
+
+    enemy_count.0 += 1;
+}
+
+/// Moves each boss on to the phase its hit points call for, flies it along that phase's
+/// path, and brings its hitboxes along. A boss that a config reload removed stays in the
+/// phase it is in.
+pub fn boss_movement_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    win_size: Res<WinSize>,
+    mut boss_query: Query<(Entity, &mut Boss, &mut BossFlight, &Health, &mut Transform)>,
+    mut part_query: Query<(&BossPart, &mut Transform), Without<Boss>>,
+) {
+    for (entity, mut boss, mut flight, health, mut transform) in boss_query.iter_mut() {
+        if let Some(boss_config) = config.bosses.get(&boss.name) {
+            let phase = boss_config.phase(health.current);
+            if phase != boss.phase {
+                if let Some(path) = phase_path(boss_config, phase, &config, &win_size) {
+                    boss.phase = phase;
+                    flight.path = path;
+                    flight.distance = 0.;
+                    commands.entity(entity).insert(phase_gun(boss_config, phase));
+                }
+            }
+            flight.speed = config.base_speed * boss_config.phases[boss.phase].speed;
+        }
+
+        let speed = flight.speed;
+        flight.distance += speed * TIME_STEP;
+        if flight.path.finished(flight.distance) {
+            flight.distance = flight.path.length();
+        }
+        let position = transform.translation.truncate();
+        let to_path = flight.path.position(flight.distance) - position;
+        let position = position + to_path.clamp_length_max(speed * CATCH_UP * TIME_STEP);
+        (transform.translation.x, transform.translation.y) = (position.x, position.y);
+    }
+
+    for (part, mut transform) in part_query.iter_mut() {
+        if let Ok((_, _, _, _, boss_tf)) = boss_query.get(part.boss) {
+            let position = boss_tf.translation.truncate() + part.offset;
+            (transform.translation.x, transform.translation.y) = (position.x, position.y);
+        }
+    }
+}
+
+/// Fires each boss's pattern for its current phase. A boss that a config reload removed
+/// stops firing.
+pub fn boss_fire_system(
+    mut commands: Commands,
+    wave: Res<CurrentWave>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    player_query: Query<&Transform, (With<Player>, Without<Boss>)>,
+    mut boss_query: Query<(Entity, &Transform, &Boss, &mut EnemyGun)>,
+) {
+    let delta = Duration::from_secs_f32(TIME_STEP * wave.config.fire_rate);
+    let player = player_query.get_single().map(|tf| tf.translation.truncate()).ok();
+
+    for (entity, tf, boss, mut gun) in boss_query.iter_mut() {
+        if !gun.timer.tick(delta).finished() {
+            continue;
+        }
+        let boss_config = match config.bosses.get(&boss.name) {
+            Some(boss_config) => boss_config,
+            None => {
+                warn!("boss `{}` is no longer in the config, it stops firing", boss.name);
+                commands.entity(entity).remove::<EnemyGun>();
+                continue;
+            }
+        };
+        let fire = &boss_config.phases[boss.phase].fire;
+        reload(&mut gun, fire);
+
+        let laser = Ammo {
+            texture: game_textures.enemy_lasers.get(&boss.name).cloned().unwrap_or_default(),
+            size: boss_config.laser_size,
+            damage: boss_config.laser_damage,
+            scale: config.sprite_scale,
+        };
+        let spacing = boss_config.size.0 * 2. / 3. * tf.scale.x;
+        spawn_volley(&mut commands, &laser, fire.pattern, &mut gun.spiral, tf, spacing, player);
+    }
+}
+
+/// Clears the hitboxes of each destroyed boss and leaves a `BossWreck` behind. Hits are
+/// taken along with those on enemies, so a laser never damages both.
+pub fn boss_destroyed_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+    part_query: Query<(Entity, &BossPart)>,
+    boss_query: Query<(&Transform, &Boss)>,
+) {
+    for event in destroyed_events.iter() {
+        let (boss_tf, boss) = match boss_query.get(event.enemy) {
+            Ok(boss) => boss,
+            Err(_) => continue,
+        };
+        for (entity, part) in part_query.iter() {
+            if part.boss == event.enemy {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        let boss_config = match config.bosses.get(&boss.name) {
+            Some(boss_config) => boss_config,
+            None => {
+                warn!("boss `{}` is no longer in the config, leaving no wreck", boss.name);
+                continue;
+            }
+        };
+        if boss_config.explosions > 0 {
+            commands.spawn().insert(BossWreck {
+                center: boss_tf.translation,
+                size: Vec2::from(boss_config.size) * boss_tf.scale.truncate(),
+                scale: boss_config.explosion_scale,
+                timer: Timer::from_seconds(boss_config.explosion_interval, true),
+                count: boss_config.explosions,
+                left: boss_config.explosions,
+            });
+        }
+    }
+}
+
+/// Sets off the explosions of each wreck one after the other, spiralling out from its
+/// center to its edges, and clears the wreck after the last one.
+pub fn boss_wreck_system(mut commands: Commands, mut query: Query<(Entity, &mut BossWreck)>) {
+    for (entity, mut wreck) in query.iter_mut() {
+        if !wreck.timer.tick(Duration::from_secs_f32(TIME_STEP)).just_finished() {
+            continue;
+        }
+
+        let i = wreck.count - wreck.left;
+        let reach = ((i + 1) as f32 / wreck.count as f32).sqrt();
+        let (sin, cos) = (i as f32 * WRECK_TURN).sin_cos();
+        let offset = Vec2::new(cos, sin) * wreck.size / 2. * reach;
+        commands
+            .spawn()
+            .insert(ExplosionToSpawn(wreck.center + offset.extend(1.)))
+            .insert(ExplosionScale(wreck.scale));
+
+        wreck.left -= 1;
+        if wreck.left == 0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::formation::{Formation, FormationMember};
+use crate::components::{layers, Collider, Damage, Diving, Enemy, Interpolated, Invulnerable, PickupKind, Player, PowerUps};
+use crate::config::GameConfig;
+use crate::events::{Collision, PlayerHit};
+use crate::rng::GameRng;
+use crate::{WinSize, TIME_STEP};
+
+/// How far past the edges of the screen divers wrap around.
+const WRAP_MARGIN: f32 = 50.;
+
+/// Sends a member of each diving formation at the player whenever the formation's dive
+/// timer runs out. Divers can ram the player until they're back in formation.
+pub fn dive_start_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    player_query: Query<(), With<Player>>,
+    mut formation_query: Query<&mut Formation>,
+    mut member_query: Query<&mut Collider, (With<Enemy>, Without<Diving>)>,
+) {
+    let player_on = !player_query.is_empty();
+    for mut formation in formation_query.iter_mut() {
+        let due = match formation.dive_timer.as_mut() {
+            Some(timer) => timer.tick(Duration::from_secs_f32(TIME_STEP)).just_finished(),
+            None => false,
+        };
+        if !due || !player_on {
+            continue;
+        }
+
+        let candidates: Vec<Entity> = formation
+            .members
+            .iter()
+            .copied()
+            .filter(|member| member_query.contains(*member))
+            .collect();
+        if candidates.is_empty() {
+            continue;
+        }
+        let diver = candidates[rng.gen_range(0..candidates.len())];
+        member_query.get_mut(diver).unwrap().mask |= layers::PLAYER;
+        commands
+            .entity(diver)
+            .insert(Diving {
+                direction: Vec2::new(0., -1.),
+                speed: config.base_speed * config.dive.speed,
+                turn_rate: config.dive.turn_rate.to_radians(),
+                rejoining: false,
+            })
+            .insert(Damage(config.dive.damage));
+    }
+}
+
+/// Flies divers: homing in on the player while above them, wrapping to the top once off
+/// the screen, then straight back to their slot in the formation.
+pub fn enemy_dive_system(
+    mut commands: Commands,
+    win_size: Res<WinSize>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    formation_query: Query<&Formation>,
+    mut query: Query<(Entity, &mut Diving, &FormationMember, &mut Transform, &mut Interpolated, &mut Collider), With<Enemy>>,
+) {
+    let player = player_query.get_single().map(|tf| tf.translation.truncate()).ok();
+    for (entity, mut diving, member, mut transform, mut interpolated, mut collider) in query.iter_mut() {
+        let position = transform.translation.truncate();
+        let step = diving.speed * TIME_STEP;
+
+        if diving.rejoining {
+            let slot = formation_query.get(member.formation).map_or(position, |formation| formation.slot(member));
+            let to_slot = slot - position;
+            let position = if to_slot.length() <= step {
+                collider.mask &= !layers::PLAYER;
+                commands.entity(entity).remove::<Diving>().remove::<Damage>();
+                slot
+            } else {
+                position + to_slot.normalize() * step
+            };
+            (transform.translation.x, transform.translation.y) = (position.x, position.y);
+            continue;
+        }
+
+        if let Some(player) = player.filter(|player| player.y < position.y) {
+            let max_turn = diving.turn_rate * TIME_STEP;
+            let (sin, cos) = diving.direction.angle_between(player - position).clamp(-max_turn, max_turn).sin_cos();
+            let direction = diving.direction;
+            diving.direction = Vec2::new(direction.x * cos - direction.y * sin, direction.x * sin + direction.y * cos);
+        }
+
+        let mut position = position + diving.direction * step;
+        if position.y < -win_size.h / 2. - WRAP_MARGIN || position.x.abs() > win_size.w / 2. + WRAP_MARGIN {
+            position.y = win_size.h / 2. + WRAP_MARGIN;
+            position.x = position.x.clamp(-win_size.w / 2., win_size.w / 2.);
+            diving.rejoining = true;
+        }
+        (transform.translation.x, transform.translation.y) = (position.x, position.y);
+        if diving.rejoining {
+            interpolated.previous = transform.translation;
+        }
+    }
+}
+
+/// Hurts the player when a diver or charger flies into them, unless shielded. Each dive or
+/// charge rams at most once: the enemy flies on and stops colliding with the player.
+pub fn enemy_ram_system(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut hit_events: EventWriter<PlayerHit>,
+    player_query: Query<(&Transform, &PowerUps), (With<Player>, Without<Invulnerable>)>,
+    mut diver_query: Query<(&Damage, &mut Collider), With<Enemy>>,
+) {
+    for collision in collisions.iter() {
+        let (player_entity, diver_entity) = match collision.pair(|entity| player_query.contains(entity)) {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let (damage, mut collider) = match diver_query.get_mut(diver_entity) {
+            Ok(diver) => diver,
+            Err(_) => continue,
+        };
+        if collider.mask & layers::PLAYER == 0 {
+            continue;
+        }
+        collider.mask &= !layers::PLAYER;
+        commands.entity(diver_entity).remove::<Damage>();
+
+        let (player_tf, power_ups) = player_query.get(player_entity).unwrap();
+        if !power_ups.active(PickupKind::Shield) {
+            hit_events.send(PlayerHit {
+                player: player_entity,
+                damage: damage.0,
+                position: player_tf.translation,
+            });
+        }
+    }
+}
@@ -1,56 +1,213 @@
-use bevy::prelude::Component;
-use rand::{thread_rng, Rng};
-use crate::{FORMATION_MEMBERS_MAX, BASE_SPEED, WinSize};
+use bevy::prelude::*;
+use rand::Rng;
+use crate::{PlayerState, WinSize, TIME_STEP};
+use crate::components::{Diving, Enemy};
+use crate::config::{FormationConfig, GameConfig};
+use crate::events::{EnemyDestroyed, ScoreAwarded};
+use crate::path::{Path, PathEnd, PathShape};
+use crate::rng::GameRng;
 
-#[derive(Clone, Component)]
+/// Sweep of strafing enemies once they're through their path's entry, in path coordinates.
+const STRAFE_RADIUS: (f32, f32) = (0.8, 0.1);
+/// How much faster or slower than the formation members fly while taking up their slot.
+const REGROUP: f32 = 0.5;
+
+/// A group of enemies flying one path. Members trail its head in the order they joined,
+/// `spacing` apart, and close ranks when one of them is destroyed.
+#[derive(Component)]
 pub struct Formation {
-    pub start: (f32, f32),
-    pub radius: (f32, f32),
-    pub pivot: (f32, f32),
+    pub path: Path,
     pub speed: f32,
-    pub angle: f32,
+    /// Distance the head of the formation has travelled along `path`.
+    pub distance: f32,
+    pub spacing: f32,
+    /// Surviving members, front first.
+    pub members: Vec<Entity>,
+    pub spawned: u32,
+    /// Members the formation is made of once it's fully spawned.
+    pub size: u32,
+    /// Awarded for destroying every member.
+    pub bonus: i64,
+    /// Time until the next member breaks out to dive, if the formation dives.
+    pub dive_timer: Option<Timer>,
+}
+
+impl Formation {
+    /// Where `member` belongs in the formation right now.
+    pub fn slot(&self, member: &FormationMember) -> Vec2 {
+        self.path.position(self.distance - member.lag.unwrap_or(self.distance))
+    }
+}
+
+/// An enemy flying in a `Formation`, `lag` behind its head. Unset until its first tick.
+#[derive(Component)]
+pub struct FormationMember {
+    pub formation: Entity,
+    pub lag: Option<f32>,
 }
 
 #[derive(Default)]
 pub struct FormationMaker {
-    current_template: Option<Formation>,
-    current_members: u32
+    current: Option<Entity>,
+    /// Wave the current formation belongs to; a new wave starts a new formation.
+    wave: u32,
 }
 
 impl FormationMaker {
-    pub fn make(&mut self, win_size: &WinSize) -> Formation {
-        match (&self.current_template, self.current_members >= FORMATION_MEMBERS_MAX) {
-            (Some(tmpl), false) => {
-                self.current_members += 1;
-                tmpl.clone()
-            }
+    /// Adds `member` to the current formation and returns where it starts, unless that
+    /// formation is full, gone or from an earlier wave.
+    pub fn join(
+        &mut self,
+        commands: &mut Commands,
+        formation_query: &mut Query<&mut Formation>,
+        member: Entity,
+        wave: u32,
+    ) -> Option<Vec2> {
+        if self.wave != wave {
+            return None;
+        }
+        let current = self.current?;
+        let mut formation = formation_query.get_mut(current).ok()?;
+        if formation.spawned >= formation.size {
+            return None;
+        }
+        formation.spawned += 1;
+        formation.members.push(member);
+        commands.entity(member).insert(FormationMember { formation: current, lag: None });
+        Some(formation.path.start())
+    }
+
+    /// Starts a new formation flying `path`, led by `member`, and returns where it starts.
+    pub fn start(
+        &mut self,
+        commands: &mut Commands,
+        member: Entity,
+        wave: u32,
+        path: Path,
+        speed: f32,
+        formation_config: &FormationConfig,
+    ) -> Vec2 {
+        let start = path.start();
+        let formation = Formation {
+            path,
+            speed,
+            distance: 0.,
+            spacing: formation_config.spacing,
+            members: vec![member],
+            spawned: 1,
+            size: formation_config.members,
+            bonus: formation_config.bonus,
+            dive_timer: (formation_config.dive_interval > 0.)
+                .then(|| Timer::from_seconds(formation_config.dive_interval, true)),
+        };
+        let formation = commands.spawn().insert(formation).id();
+        commands.entity(member).insert(FormationMember { formation, lag: None });
+        self.current = Some(formation);
+        self.wave = wave;
+        start
+    }
+}
 
-            (None, _) | (_, true) => {
+/// Picks one of the formation's paths, mirrored or not. Strafing formations fly the entry,
+/// then sweep the width of the screen at the height it ended. None if a config reload
+/// removed the path picked.
+pub fn formation_path(
+    config: &GameConfig,
+    formation_config: &FormationConfig,
+    win_size: &WinSize,
+    strafe: bool,
+    rng: &mut GameRng,
+) -> Option<Path> {
+    let name = &formation_config.paths[rng.gen_range(0..formation_config.paths.len())];
+    let mirror = rng.gen_bool(0.5);
+    let path_config = match config.paths.get(name) {
+        Some(path_config) => path_config,
+        None => {
+            warn!("path `{name}` is no longer in the config, skipping the formation");
+            return None;
+        }
+    };
+    let scale = Vec2::new(win_size.w / 2., win_size.h / 2.);
+    if strafe {
+        let entry = path_config.entry.sample();
+        let end_y = entry.last().unwrap().y;
+        let sweep = PathEnd::Loop(PathShape::Ellipse { center: (0., end_y), radius: STRAFE_RADIUS });
+        Some(Path::new(&path_config.entry, &sweep, scale, mirror))
+    } else {
+        Some(Path::new(&path_config.entry, &path_config.then, scale, mirror))
+    }
+}
 
-                let mut rng = thread_rng();
-                let w_span = win_size.w / 2. + 100.;
-                let h_span = win_size.h / 2. + 100.;
-                let x = if rng.gen_bool(0.5) { w_span } else { -w_span };
-                let y = rng.gen_range(-h_span..h_span) as f32;
-                let start = (x, y);
+/// Advances every formation along its path and places its members behind the head,
+/// speeding them up or slowing them down until they're in their slot. Diving members are
+/// left to `enemy_dive_system`.
+pub fn enemy_movement_system(
+    mut formation_query: Query<&mut Formation>,
+    mut member_query: Query<(Entity, &mut FormationMember, &mut Transform), (With<Enemy>, Without<Diving>)>,
+) {
+    for mut formation in formation_query.iter_mut() {
+        formation.distance += formation.speed * TIME_STEP;
+        if formation.path.finished(formation.distance) {
+            formation.distance = formation.path.length();
+        }
+    }
 
-                let w_span = win_size.w / 4.;
-                let h_span = win_size.h / 3. + 50.;
-                let pivot = (rng.gen_range(-w_span..w_span), rng.gen_range(0.0..h_span));
-                let radius = (rng.gen_range(80.0..150.), 100.);
-                let angle = (y - pivot.1).atan2(x - pivot.0);
-                let speed = BASE_SPEED;
+    for (entity, mut member, mut transform) in member_query.iter_mut() {
+        let formation = match formation_query.get(member.formation) {
+            Ok(formation) => formation,
+            Err(_) => continue,
+        };
+        let rank = formation.members.iter().position(|m| *m == entity).unwrap_or(0);
+        let target = rank as f32 * formation.spacing;
 
-                let formation = Formation {
-                    start, radius, pivot, speed, angle,
-                };
+        // Joining members start from the beginning of the path.
+        let lag = member.lag.get_or_insert(formation.distance);
+        if let Some((loop_start, loop_length)) = formation.path.looped_part() {
+            // Once in the loop, a lap behind is as good as in place.
+            if formation.distance - *lag >= loop_start && loop_length > 0. {
+                *lag -= ((*lag - target) / loop_length).floor().max(0.) * loop_length;
+            }
+        }
+        let regroup = formation.speed * REGROUP * TIME_STEP;
+        *lag -= (*lag - target).clamp(-regroup, regroup);
 
-                self.current_template = Some(formation.clone());
-                self.current_members += 1;
+        let position = formation.path.position(formation.distance - *lag);
+        (transform.translation.x, transform.translation.y) = (position.x, position.y);
+    }
+}
 
-                formation
+/// Drops destroyed enemies from their formation, awards the bonus when that wipes out a
+/// whole formation, and despawns formations left without members for good.
+pub fn formation_wipe_system(
+    mut commands: Commands,
+    maker: Res<FormationMaker>,
+    mut player_state: ResMut<PlayerState>,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+    mut awarded_events: EventWriter<ScoreAwarded>,
+    mut formation_query: Query<(Entity, &mut Formation)>,
+) {
+    for event in destroyed_events.iter() {
+        for (_, mut formation) in formation_query.iter_mut() {
+            let i = match formation.members.iter().position(|m| *m == event.enemy) {
+                Some(i) => i,
+                None => continue,
+            };
+            formation.members.remove(i);
+            if formation.members.is_empty() && formation.spawned >= formation.size && formation.size > 1 {
+                player_state.score += formation.bonus;
+                awarded_events.send(ScoreAwarded {
+                    points: formation.bonus,
+                    multiplier: 1,
+                    position: event.position,
+                });
             }
+        }
+    }
 
+    for (entity, formation) in formation_query.iter() {
+        let complete = formation.spawned >= formation.size || maker.current != Some(entity);
+        if formation.members.is_empty() && complete {
+            commands.entity(entity).despawn();
         }
     }
 }
@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use super::insert_enemy;
+use crate::components::{Enemy, GridFormation, GridMember};
+use crate::config::{GameConfig, WaveConfig};
+use crate::rng::GameRng;
+use crate::{GameTextures, WinSize, TIME_STEP};
+
+/// Closest a member's center gets to the side of the screen before the grid turns.
+const EDGE_MARGIN: f32 = 40.;
+/// Height above the bottom of the screen below which the grid stops stepping down.
+const FLOOR: f32 = 160.;
+
+/// Spawns the wave's grid, one enemy kind per row, and its controller. Returns the number
+/// of enemies spawned, which is 0 for waves without a grid. Rows of a kind that a config
+/// reload removed are left out.
+pub fn spawn_grid(
+    commands: &mut Commands,
+    wave: &WaveConfig,
+    config: &GameConfig,
+    game_textures: &GameTextures,
+    win_size: &WinSize,
+    rng: &mut GameRng,
+) -> u32 {
+    let grid = match &wave.grid {
+        Some(grid) => grid,
+        None => return 0,
+    };
+
+    let (dx, dy) = grid.spacing;
+    let origin = Vec2::new(0., win_size.h / 2. - dy);
+    let controller = commands.spawn().id();
+    let mut members = 0;
+    for row in 0..grid.rows {
+        let name = wave.pick_enemy(rng).to_string();
+        let kind = match config.enemies.get(&name) {
+            Some(kind) => kind,
+            None => {
+                warn!("enemy kind `{name}` is no longer in the config, leaving its grid row out");
+                continue;
+            }
+        };
+        for column in 0..grid.columns {
+            let offset = Vec2::new(
+                (column as f32 - (grid.columns - 1) as f32 / 2.) * dx,
+                -(row as f32) * dy,
+            );
+            let translation = (origin + offset).extend(10.);
+            let mut entity = commands.spawn();
+            entity.insert(GridMember { grid: controller, offset });
+            insert_enemy(&mut entity, &name, kind, translation, config, game_textures, rng);
+            members += 1;
+        }
+    }
+
+    commands.entity(controller).insert(GridFormation {
+        origin,
+        direction: 1.,
+        speed: config.base_speed * wave.formation.speed,
+        speed_up: grid.speed_up,
+        step_down: grid.step_down,
+        step_left: 0.,
+        members,
+    });
+    members
+}
+
+/// Marches every grid sideways, steps it down when its outermost surviving member reaches
+/// the edge of the screen, and moves the members with it. Grids with no members left are
+/// despawned.
+pub fn grid_march_system(
+    mut commands: Commands,
+    win_size: Res<WinSize>,
+    mut grid_query: Query<(Entity, &mut GridFormation)>,
+    mut member_query: Query<(&GridMember, &mut Transform), With<Enemy>>,
+) {
+    // Alive members and the extent of their offsets, per grid.
+    let mut extents: HashMap<Entity, (u32, f32, f32, f32)> = HashMap::default();
+    for (member, _) in member_query.iter() {
+        let extent = extents.entry(member.grid).or_insert((0, f32::MAX, f32::MIN, f32::MAX));
+        extent.0 += 1;
+        extent.1 = extent.1.min(member.offset.x);
+        extent.2 = extent.2.max(member.offset.x);
+        extent.3 = extent.3.min(member.offset.y);
+    }
+
+    for (entity, mut grid) in grid_query.iter_mut() {
+        let (alive, left, right, bottom) = match extents.get(&entity) {
+            Some(extent) => *extent,
+            None => {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        };
+
+        let mut distance = grid.current_speed(alive) * TIME_STEP;
+        if grid.step_left > 0. {
+            let step = distance.min(grid.step_left);
+            grid.origin.y -= step;
+            grid.step_left -= step;
+            distance -= step;
+        }
+        grid.origin.x += grid.direction * distance;
+
+        let edge = win_size.w / 2. - EDGE_MARGIN;
+        let past_edge = if grid.direction > 0. {
+            grid.origin.x + right - edge
+        } else {
+            -edge - (grid.origin.x + left)
+        };
+        if past_edge > 0. {
+            grid.origin.x -= grid.direction * past_edge;
+            grid.direction = -grid.direction;
+            if grid.origin.y + bottom > -win_size.h / 2. + FLOOR {
+                grid.step_left = grid.step_down;
+            }
+        }
+    }
+
+    for (member, mut transform) in member_query.iter_mut() {
+        if let Ok((_, grid)) = grid_query.get(member.grid) {
+            let position = grid.origin + member.offset;
+            (transform.translation.x, transform.translation.y) = (position.x, position.y);
+        }
+    }
+}
@@ -1,141 +1,370 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
 use crate::TIME_STEP;
-use bevy::time::FixedTimestep;
-use core::f32::consts::PI;
-use bevy::ecs::schedule::ShouldRun;
+use core::f32::consts::{PI, TAU};
+use crate::{playing_criteria, AppState};
+use crate::components::{layers, Boss, BossPart, Collider, Damage, EnemyGun, EnemyKind, FirePattern, GridFormation, Health, Interpolated, MovementStyle, Player};
+use crate::broadphase::Movement;
+use crate::events::{CollisionDetection, CollisionResponse};
+use crate::timestep::FixedUpdateStage;
+use crate::wave::CurrentWave;
 use crate::Velocity;
 use crate::Movable;
 use crate::components::FromEnemy;
-use crate::ENEMEY_LASER_SIZE;
 use crate::Laser;
-use crate::ENEMY_MAX;
 use crate::EnemyCount;
+use crate::config::{EnemyKindConfig, FireConfig, GameConfig};
 use rand::Rng;
 use crate::components::SpriteSize;
-use rand::thread_rng;
-use crate::{GameTextures, WinSize, SPRITE_SCALE, ENEMY_SIZE};
-use self::formation::{Formation, FormationMaker};
+use crate::rng::GameRng;
+use crate::{GameTextures, WinSize};
+use self::boss::BossWreck;
+use self::dive::{dive_start_system, enemy_dive_system, enemy_ram_system};
+use self::formation::{enemy_movement_system, formation_path, formation_wipe_system, Formation, FormationMaker};
 use crate::components::Enemy;
 
+use bevy::ecs::system::{EntityCommands, SystemParam};
 use bevy::prelude::*;
 
+mod boss;
+mod dive;
 mod formation;
+mod grid;
 
 pub struct EnemyPlugin;
 
+/// Label of the spawn system, the first of the enemy systems drawing from `GameRng`.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct EnemySpawn;
+
+/// Label of the system moving formations, which divers rejoin.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct FormationMovement;
+
+/// How far above and below the screen charging enemies wrap around.
+const CHARGE_MARGIN: f32 = 50.;
+
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(FormationMaker::default())
-            .add_system_set(
+            .add_system_set_to_stage(
+            FixedUpdateStage,
             SystemSet::new()
-            .with_run_criteria(FixedTimestep::step(1.0))
-            .with_system(enemy_spawn_system)
+            .with_run_criteria(playing_criteria)
+            .with_system(enemy_spawn_system.label(EnemySpawn).before(Movement))
+            .with_system(enemy_fire_system)
+            .with_system(enemy_movement_system.label(Movement).label(FormationMovement))
+            .with_system(enemy_charge_system.label(Movement))
+            .with_system(grid::grid_march_system.label(Movement))
+            .with_system(dive_start_system.after(EnemySpawn).before(Movement))
+            .with_system(enemy_dive_system.label(Movement).after(FormationMovement))
+            .with_system(enemy_ram_system.label(CollisionResponse).after(CollisionDetection))
+            .with_system(formation_wipe_system.after(CollisionResponse))
+            .with_system(boss::boss_spawn_system)
+            .with_system(boss::boss_movement_system.label(Movement))
+            .with_system(boss::boss_fire_system.after(Movement))
+            .with_system(boss::boss_destroyed_system.after(CollisionResponse))
+            .with_system(boss::boss_wreck_system),
         )
-        .add_system_set(
-            SystemSet::new()
-            .with_run_criteria(enemy_fire_criteria)
-            .with_system(enemy_fire_system),
-        )
-        .add_system(enemy_movement_system);
+        .add_system_set(SystemSet::on_exit(AppState::GameOver).with_system(enemy_reset_system));
+    }
+}
+
+fn enemy_reset_system(
+    mut commands: Commands,
+    mut formation_maker: ResMut<FormationMaker>,
+    query: Query<Entity, Or<(With<Formation>, With<GridFormation>, With<Boss>, With<BossPart>, With<BossWreck>)>>,
+) {
+    *formation_maker = FormationMaker::default();
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
     }
 }
 
+/// What spawning an enemy looks up: its tuning, its sprites and the size of the screen.
+#[derive(SystemParam)]
+struct SpawnContext<'w, 's> {
+    config: Res<'w, GameConfig>,
+    game_textures: Res<'w, GameTextures>,
+    win_size: Res<'w, WinSize>,
+    #[system_param(ignore)]
+    _marker: PhantomData<&'s ()>,
+}
+
 fn enemy_spawn_system(
-    mut commands: Commands, 
+    mut commands: Commands,
+    mut wave: ResMut<CurrentWave>,
     mut enemy_count: ResMut<EnemyCount>,
     mut formation_maker: ResMut<FormationMaker>,
-    game_textures: Res<GameTextures>,
-    win_size: Res<WinSize>
+    mut formation_query: Query<&mut Formation>,
+    mut rng: ResMut<GameRng>,
+    context: SpawnContext,
 ) {
-    if enemy_count.0 < ENEMY_MAX {
-        let formation = formation_maker.make(&win_size);
-        let (x, y) = formation.start;
+    let (config, game_textures, win_size) = (&*context.config, &*context.game_textures, &*context.win_size);
+    if wave.spawn_due() {
+        if wave.config.grid.is_some() {
+            let spawned = grid::spawn_grid(&mut commands, &wave.config, config, game_textures, win_size, &mut rng);
+            enemy_count.0 += spawned;
+            // Rows left out still count, so that the wave moves on.
+            for _ in 0..wave.config.enemy_count {
+                wave.spawned_one();
+            }
+            return;
+        }
 
-        commands
-            .spawn_bundle(SpriteBundle {
-                texture: game_textures.enemy.clone(),
-                transform: Transform {
-                    translation: Vec3::new(x, y, 10.0),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.0),
-                    ..Default::default()
-                },
-                ..Default::default()
-            })
-            .insert(Enemy)
-            .insert(formation)
-            .insert(SpriteSize::from(ENEMY_SIZE));
+        let name = wave.config.pick_enemy(&mut *rng).to_string();
+        let kind = match config.enemies.get(&name) {
+            Some(kind) => kind,
+            None => {
+                warn!("enemy kind `{name}` is no longer in the config, skipping it");
+                wave.spawned_one();
+                return;
+            }
+        };
+        let speed = config.base_speed * wave.config.formation.speed * kind.speed;
+
+        let id = commands.spawn().id();
+        let translation = match kind.movement {
+            MovementStyle::Orbit | MovementStyle::Strafe => {
+                let joined = formation_maker.join(&mut commands, &mut formation_query, id, wave.number);
+                let start = match joined {
+                    Some(start) => start,
+                    None => {
+                        let strafe = kind.movement == MovementStyle::Strafe;
+                        match formation_path(config, &wave.config.formation, win_size, strafe, &mut rng) {
+                            Some(path) => {
+                                formation_maker.start(&mut commands, id, wave.number, path, speed, &wave.config.formation)
+                            }
+                            None => {
+                                commands.entity(id).despawn();
+                                wave.spawned_one();
+                                return;
+                            }
+                        }
+                    }
+                };
+                Vec3::new(start.x, start.y, 10.0)
+            }
+            MovementStyle::Charge => {
+                let x = rng.gen_range(-win_size.w / 2.0..win_size.w / 2.0);
+                commands
+                    .entity(id)
+                    .insert(Movable { auto_despawn: false })
+                    .insert(Velocity { x: 0., y: -speed / config.base_speed })
+                    .insert(Damage(config.dive.damage));
+                Vec3::new(x, win_size.h / 2. + CHARGE_MARGIN, 10.0)
+            }
+        };
+
+        let mut entity = commands.entity(id);
+        insert_enemy(&mut entity, &name, kind, translation, config, game_textures, &mut rng);
 
         enemy_count.0 += 1;
+        wave.spawned_one();
     }
 }
 
-fn enemy_fire_criteria() -> ShouldRun {
-    if thread_rng().gen_bool(1. / 60.) {
-        ShouldRun::Yes
-    } else {
-        ShouldRun::No
+/// Makes `entity` an enemy of kind `name`, configured by `kind`, at `translation`, leaving
+/// how it moves to the caller.
+fn insert_enemy(
+    entity: &mut EntityCommands,
+    name: &str,
+    kind: &EnemyKindConfig,
+    translation: Vec3,
+    config: &GameConfig,
+    game_textures: &GameTextures,
+    rng: &mut GameRng,
+) {
+    let scale = config.sprite_scale * kind.scale;
+    let (r, g, b) = kind.color;
+    // Chargers ram the player on their way down.
+    let mask = match kind.movement {
+        MovementStyle::Charge => layers::PLAYER_LASER | layers::PLAYER,
+        MovementStyle::Orbit | MovementStyle::Strafe => layers::PLAYER_LASER,
+    };
+    let mut fire_timer = Timer::from_seconds(kind.fire.interval, false);
+    fire_timer.set_elapsed(Duration::from_secs_f32(rng.gen_range(0.0..kind.fire.interval)));
+
+    entity
+        .insert_bundle(SpriteBundle {
+            texture: game_textures.enemies.get(name).cloned().unwrap_or_default(),
+            sprite: Sprite {
+                color: Color::rgb(r, g, b),
+                ..Default::default()
+            },
+            transform: Transform {
+                translation,
+                scale: Vec3::new(scale, scale, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Enemy)
+        .insert(kind.kind(name))
+        .insert(Health::new(kind.hit_points))
+        .insert(EnemyGun { timer: fire_timer, burst_left: kind.fire.burst, spiral: 0. })
+        .insert(Interpolated::from(translation))
+        .insert(SpriteSize::from(kind.size))
+        .insert(Collider::aabb(kind.size, layers::ENEMY, mask));
+}
+
+/// Offsets along x and angles (radians from straight down towards +x) of the lasers in one
+/// volley of `pattern`. `aim` is the angle towards the player, and `spiral` the turn of the
+/// enemy's spiral, advanced by every spiral volley.
+fn volley(pattern: FirePattern, aim: f32, spiral: &mut f32, spacing: f32) -> Vec<(f32, f32)> {
+    let fan = |count: u32, arc: f32| -> Vec<f32> {
+        if count == 1 {
+            return vec![0.];
+        }
+        let step = arc.to_radians() / (count - 1) as f32;
+        (0..count).map(|i| i as f32 * step - arc.to_radians() / 2.).collect()
+    };
+
+    match pattern {
+        FirePattern::Silent => Vec::new(),
+        FirePattern::Straight { count } => (0..count)
+            .map(|i| ((i as f32 - (count - 1) as f32 / 2.) * spacing, 0.))
+            .collect(),
+        FirePattern::Aimed => vec![(0., aim)],
+        FirePattern::Spread { count, arc, aimed } => {
+            let center = if aimed { aim } else { 0. };
+            fan(count, arc).into_iter().map(|angle| (0., center + angle)).collect()
+        }
+        FirePattern::Spiral { count, step } => {
+            let start = *spiral;
+            *spiral = (*spiral + step.to_radians()) % TAU;
+            (0..count).map(|i| (0., start + i as f32 * TAU / count as f32)).collect()
+        }
     }
 }
 
+/// Fires each enemy's pattern when its own timer runs out, volley by volley through a burst.
+/// Enemies whose kind a config reload removed stop firing.
 fn enemy_fire_system(
     mut commands: Commands,
+    wave: Res<CurrentWave>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    enemy_query: Query<&Transform, With<Enemy>>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<(Entity, &Transform, &EnemyKind, &mut EnemyGun), With<Enemy>>,
+) {
+    let delta = Duration::from_secs_f32(TIME_STEP * wave.config.fire_rate);
+    let player = player_query.get_single().map(|tf| tf.translation.truncate()).ok();
+
+    for (entity, tf, enemy_kind, mut gun) in enemy_query.iter_mut() {
+        if !gun.timer.tick(delta).finished() {
+            continue;
+        }
+        let kind = match config.enemies.get(&enemy_kind.name) {
+            Some(kind) => kind,
+            None => {
+                warn!("enemy kind `{}` is no longer in the config, its enemies stop firing", enemy_kind.name);
+                commands.entity(entity).remove::<EnemyGun>();
+                continue;
+            }
+        };
+        reload(&mut gun, &kind.fire);
+
+        let laser = Ammo {
+            texture: game_textures.enemy_lasers.get(&enemy_kind.name).cloned().unwrap_or_default(),
+            size: kind.laser_size,
+            damage: kind.laser_damage,
+            scale: config.sprite_scale,
+        };
+        let spacing = kind.size.0 * 2. / 3. * tf.scale.x;
+        spawn_volley(&mut commands, &laser, enemy_kind.fire, &mut gun.spiral, tf, spacing, player);
+    }
+}
+
+/// Sets `gun` up for its next volley: the next one of the burst, or the first of the next
+/// burst once this one is through.
+fn reload(gun: &mut EnemyGun, fire: &FireConfig) {
+    if gun.burst_left > 1 {
+        gun.burst_left -= 1;
+        gun.timer = Timer::from_seconds(fire.burst_interval, false);
+    } else {
+        gun.burst_left = fire.burst;
+        gun.timer = Timer::from_seconds(fire.interval, false);
+    }
+}
+
+/// The lasers something fires.
+struct Ammo {
+    texture: Handle<Image>,
+    size: (f32, f32),
+    damage: u32,
+    /// Sprite scale.
+    scale: f32,
+}
+
+/// Spawns one volley of `pattern` from just below `tf`, aimed at `player` if the pattern
+/// aims. Side by side lasers are `spacing` apart.
+fn spawn_volley(
+    commands: &mut Commands,
+    laser: &Ammo,
+    pattern: FirePattern,
+    spiral: &mut f32,
+    tf: &Transform,
+    spacing: f32,
+    player: Option<Vec2>,
 ) {
-    for &tf in enemy_query.iter() {
-        let (x, y) = (tf.translation.x, tf.translation.y);
+    let (x, y) = (tf.translation.x, tf.translation.y - 15.);
+    let aim = player.map_or(0., |player| (player.x - x).atan2(y - player.y));
+
+    for (x_offset, angle) in volley(pattern, aim, spiral, spacing) {
+        let translation = Vec3::new(x + x_offset, y, 0.);
         commands
             .spawn_bundle(SpriteBundle {
-                texture: game_textures.enemy_laser.clone(),
+                texture: laser.texture.clone(),
                 transform: Transform {
-                    translation: Vec3::new(x, y - 15., 0.),
-                    rotation: Quat::from_rotation_x(PI),
-                    scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
+                    translation,
+                    rotation: Quat::from_rotation_z(angle) * Quat::from_rotation_x(PI),
+                    scale: Vec3::new(laser.scale, laser.scale, 1.),
                     ..Default::default()
                 },
                 ..Default::default()
             })
             .insert(Laser)
-            .insert(SpriteSize::from(ENEMEY_LASER_SIZE))
+            .insert(SpriteSize::from(laser.size))
+            .insert(Collider::rotated_aabb(laser.size, angle, layers::ENEMY_LASER, layers::PLAYER))
+            .insert(Damage(laser.damage))
             .insert(FromEnemy)
+            .insert(Interpolated::from(translation))
             .insert(Movable { auto_despawn: true })
-            .insert(Velocity { x: 0., y: -1. });
-
+            .insert(Velocity { x: angle.sin(), y: -angle.cos() });
     }
 }
 
-fn enemy_movement_system(
-    time: Res<Time>, 
-    mut query: Query<(&mut Transform, &mut Formation), With<Enemy>>
+/// Steers charging enemies towards the player and sends them back to the top, ready to ram
+/// again, once they have passed the bottom of the screen.
+fn enemy_charge_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    win_size: Res<WinSize>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut query: Query<(Entity, &mut Transform, &mut Velocity, &mut Interpolated, &mut Collider, &EnemyKind), With<Enemy>>,
 ) {
-    let _now = time.seconds_since_startup() as f32;
-    for (mut transform, mut formation) in query.iter_mut() {
-        let (x_org, y_org) = (transform.translation.x, transform.translation.y);
-        let max_distance = TIME_STEP * formation.speed;
-        let dir: f32 = if formation.start.0 < 0. { 1. } else { -1. };
-        let (x_pivot, y_pivot) = formation.pivot;
-        let (x_radius, y_radius) = formation.radius;
-
-        let angle = formation.angle + dir * formation.speed * TIME_STEP / (x_radius.min(y_radius) * PI / 2.);
-        let x_dst = x_radius * angle.cos() + x_pivot;
-        let y_dst = y_radius * angle.sin() + y_pivot;
-
-
-        let dx = x_org - x_dst;
-        let dy = y_org - y_dst;
-        let distance = (dx * dx + dy * dy).sqrt();
-        let distance_ratio = if distance != 0. { max_distance / distance } else { 0. };
-
-        let x = x_org - dx * distance_ratio;
-        let x = if dx > 0. { x.max(x_dst) } else { x.min(x_dst) };
-        let y = y_org - dy * distance_ratio;
-        let y = if dy > 0. { y.max(y_dst) } else { y.min(y_dst) };
-
-        if distance < max_distance * formation.speed / 20. {
-            formation.angle = angle;
+    let player_x = player_query.get_single().map(|tf| tf.translation.x).ok();
+    for (entity, mut transform, mut velocity, mut interpolated, mut collider, kind) in query.iter_mut() {
+        if kind.movement != MovementStyle::Charge {
+            continue;
         }
 
-        let translation = &mut transform.translation;
-        (translation.x, translation.y) = (x, y);
+        let speed = velocity.y.abs();
+        if let Some(player_x) = player_x {
+            let dx = (player_x - transform.translation.x) / (win_size.w / 2.);
+            velocity.x = dx.clamp(-1., 1.) * speed;
+        }
+
+        if transform.translation.y < -win_size.h / 2. - CHARGE_MARGIN {
+            transform.translation.x = rng.gen_range(-win_size.w / 2.0..win_size.w / 2.0);
+            transform.translation.y = win_size.h / 2. + CHARGE_MARGIN;
+            velocity.x = 0.;
+            interpolated.previous = transform.translation;
+            collider.mask |= layers::PLAYER;
+            commands.entity(entity).insert(Damage(config.dive.damage));
+        }
     }
-    
 }
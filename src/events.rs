@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+
+use crate::components::PickupKind;
+
+/// Label of the system sending `Collision` pairs.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollisionDetection;
+
+/// Label of the systems turning `Collision` pairs into gameplay events; reactions to those
+/// run after it within a tick.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CollisionResponse;
+
+/// Two colliders overlap, in no particular order.
+#[derive(Debug, Clone, Copy)]
+pub struct Collision {
+    pub a: Entity,
+    pub b: Entity,
+}
+
+impl Collision {
+    /// The pair with the entity matching `first` in front, or `None` if neither does.
+    pub fn pair(&self, first: impl Fn(Entity) -> bool) -> Option<(Entity, Entity)> {
+        if first(self.a) {
+            Some((self.a, self.b))
+        } else if first(self.b) {
+            Some((self.b, self.a))
+        } else {
+            None
+        }
+    }
+}
+
+/// An enemy lost its last hit point. The entity is already despawned.
+#[derive(Debug, Clone)]
+pub struct EnemyDestroyed {
+    pub enemy: Entity,
+    pub kind: String,
+    pub score: i64,
+    pub position: Vec3,
+}
+
+/// Points scored for a kill, before `multiplier` is applied.
+#[derive(Debug, Clone)]
+pub struct ScoreAwarded {
+    pub points: i64,
+    pub multiplier: u32,
+    pub position: Vec3,
+}
+
+/// The player picked up a power-up.
+#[derive(Debug, Clone)]
+pub struct PickupCollected {
+    pub kind: PickupKind,
+    pub position: Vec3,
+}
+
+/// An enemy laser hit the player.
+#[derive(Debug, Clone)]
+pub struct PlayerHit {
+    pub player: Entity,
+    pub damage: u32,
+    pub position: Vec3,
+}
+
+/// The player ran out of health.
+#[derive(Debug, Clone)]
+pub struct PlayerDied {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+/// A laser hit something and was despawned.
+#[derive(Debug, Clone)]
+pub struct LaserImpact {
+    pub position: Vec3,
+    pub from_player: bool,
+}
+
+/// Registers the gameplay events shared by the simulation and the client.
+pub struct GameEventsPlugin;
+
+impl Plugin for GameEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Collision>()
+            .add_event::<EnemyDestroyed>()
+            .add_event::<ScoreAwarded>()
+            .add_event::<PickupCollected>()
+            .add_event::<PlayerHit>()
+            .add_event::<PlayerDied>()
+            .add_event::<LaserImpact>();
+    }
+}
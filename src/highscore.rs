@@ -0,0 +1,110 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Entries kept in the table.
+pub const MAX_ENTRIES: usize = 10;
+/// Letters in a player's initials.
+pub const INITIALS_LEN: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: i64,
+    pub wave: u32,
+    /// Day the score was set, as `YYYY-MM-DD`.
+    pub date: String,
+}
+
+/// Best scores, highest first. Saved to `path` after every new entry; without a path (as
+/// in headless runs) the table only lives in memory.
+#[derive(Debug, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+    pub path: Option<PathBuf>,
+}
+
+impl HighScores {
+    /// Reads the table at `path`, starting empty if there is none yet or it can't be read.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match fs::read_to_string(&path) {
+            Ok(text) => ron::from_str(&text).unwrap_or_else(|err| {
+                warn!("ignoring unreadable high scores {}: {err}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self { entries, path: Some(path) }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(&self.entries, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        fs::write(path, text)
+    }
+
+    pub fn best(&self) -> Option<i64> {
+        self.entries.first().map(|entry| entry.score)
+    }
+
+    /// Whether `score` would make it into the table.
+    pub fn qualifies(&self, score: i64) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_ENTRIES
+                || self.entries.last().map_or(true, |last| score > last.score))
+    }
+
+    /// Adds `entry` below any equal scores and returns its rank, or `None` if it didn't
+    /// make the table.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self.entries.iter().position(|other| entry.score > other.score).unwrap_or(self.entries.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+/// Where the client keeps its table: `orion/highscores.ron` in the user's data directory,
+/// or the working directory if there is none.
+pub fn default_path() -> PathBuf {
+    dirs::data_dir()
+        .map(|dir| dir.join("orion"))
+        .unwrap_or_default()
+        .join("highscores.ron")
+}
+
+/// Today's date in UTC, as `YYYY-MM-DD`.
+pub fn today() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Calendar date of a day count since 1970-01-01 (Howard Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
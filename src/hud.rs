@@ -0,0 +1,413 @@
+use bevy::prelude::*;
+
+use crate::components::{
+    BestScoreText, Boss, BossBar, BossBarFill, FloatingText, Health, HealthPips, LifeIcons, StatusHud, WaveBanner,
+    WaveText,
+};
+use crate::config::GameConfig;
+use crate::events::ScoreAwarded;
+use crate::highscore::HighScores;
+use crate::wave::{CurrentWave, WavePhase};
+use crate::{AppState, GameTextures, PlayerState};
+
+const PIP_SIZE: f32 = 14.;
+const PIP_FULL: Color = Color::rgb(0.3, 0.9, 0.4);
+const PIP_EMPTY: Color = Color::rgba(1., 1., 1., 0.2);
+/// Life icons are the player ship at this fraction of its sprite size.
+const ICON_SCALE: f32 = 0.25;
+const FLOATING_TEXT_SECS: f32 = 0.8;
+/// Pixels per second the score popups rise.
+const FLOATING_TEXT_SPEED: f32 = 60.;
+const BOSS_BAR_HEIGHT: f32 = 16.;
+const BOSS_BAR_FRAME: Color = Color::rgba(1., 1., 1., 0.3);
+const BOSS_BAR_FILL: Color = Color::rgb(0.9, 0.2, 0.3);
+
+/// In-game overlays drawn on top of the playfield. Needs the asset server for fonts.
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(hud_setup_system)
+            .add_system(wave_banner_system)
+            .add_system(status_visibility_system)
+            .add_system(wave_text_system)
+            .add_system(best_score_text_system)
+            .add_system(health_pips_system)
+            .add_system(life_icons_system)
+            .add_system(boss_bar_system)
+            .add_system(floating_text_spawn_system)
+            .add_system(floating_text_system);
+    }
+}
+
+fn hud_setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 70.0,
+                        color: Color::GOLD,
+                    },
+                ))
+                .insert(WaveBanner);
+        });
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Px(40.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(10.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                padding: UiRect::new(Val::Px(15.0), Val::Px(15.0), Val::Px(0.0), Val::Px(0.0)),
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(StatusHud)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                    },
+                ))
+                .insert(WaveText)
+                .insert(StatusHud);
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 30.0,
+                        color: Color::GOLD,
+                    },
+                ))
+                .insert(BestScoreText)
+                .insert(StatusHud);
+            parent
+                .spawn_bundle(row_bundle())
+                .insert(HealthPips)
+                .insert(StatusHud);
+            parent
+                .spawn_bundle(row_bundle())
+                .insert(LifeIcons)
+                .insert(StatusHud);
+        });
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Px(BOSS_BAR_HEIGHT)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(60.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(60.0), Val::Percent(100.0)),
+                        padding: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    color: BOSS_BAR_FRAME.into(),
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                })
+                .insert(BossBar)
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                                ..default()
+                            },
+                            color: BOSS_BAR_FILL.into(),
+                            visibility: Visibility { is_visible: false },
+                            ..default()
+                        })
+                        .insert(BossBar)
+                        .insert(BossBarFill);
+                });
+        });
+}
+
+fn row_bundle() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        color: Color::NONE.into(),
+        ..default()
+    }
+}
+
+fn in_game(app_state: &State<AppState>) -> bool {
+    matches!(app_state.current(), AppState::Playing | AppState::Paused)
+}
+
+/// Hides the status bar outside of a run. Visibility isn't inherited by UI children, so
+/// every part of it carries `StatusHud`.
+fn status_visibility_system(
+    app_state: Res<State<AppState>>,
+    mut query: Query<&mut Visibility, With<StatusHud>>,
+) {
+    if !app_state.is_changed() {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = in_game(&app_state);
+    }
+}
+
+fn wave_text_system(wave: Res<CurrentWave>, mut query: Query<&mut Text, With<WaveText>>) {
+    if !wave.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        let value = format!("Wave {}", wave.number);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+/// Shows the top of the high-score table, or the current score once it's higher.
+fn best_score_text_system(
+    player_state: Res<PlayerState>,
+    high_scores: Res<HighScores>,
+    mut query: Query<&mut Text, With<BestScoreText>>,
+) {
+    if !player_state.is_changed() && !high_scores.is_changed() {
+        return;
+    }
+
+    let best = high_scores.best().unwrap_or(0).max(player_state.score);
+    for mut text in query.iter_mut() {
+        let value = format!("Best: {best}");
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+/// Keeps one pip per hull point of the configured ship, lit for those the ship has left.
+fn health_pips_system(
+    mut commands: Commands,
+    player_state: Res<PlayerState>,
+    config: Res<GameConfig>,
+    app_state: Res<State<AppState>>,
+    rows: Query<(Entity, Option<&Children>), With<HealthPips>>,
+    mut pips: Query<&mut UiColor>,
+) {
+    if !player_state.is_changed() && !config.is_changed() {
+        return;
+    }
+
+    let health = player_state.health.max(0) as usize;
+    for (row, children) in rows.iter() {
+        let count = children.map_or(0, |children| children.len());
+        if count != config.player.health as usize {
+            commands.entity(row).despawn_descendants();
+            commands.entity(row).with_children(|parent| {
+                for i in 0..config.player.health as usize {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(PIP_SIZE), Val::Px(PIP_SIZE)),
+                                margin: UiRect::all(Val::Px(2.0)),
+                                ..default()
+                            },
+                            color: UiColor(pip_color(i < health)),
+                            visibility: Visibility { is_visible: in_game(&app_state) },
+                            ..default()
+                        })
+                        .insert(StatusHud);
+                }
+            });
+            continue;
+        }
+
+        if let Some(children) = children {
+            for (i, child) in children.iter().enumerate() {
+                if let Ok(mut color) = pips.get_mut(*child) {
+                    let target = pip_color(i < health);
+                    if color.0 != target {
+                        color.0 = target;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn pip_color(full: bool) -> Color {
+    if full {
+        PIP_FULL
+    } else {
+        PIP_EMPTY
+    }
+}
+
+/// Rebuilds the row of ship icons when the number of lives changes.
+fn life_icons_system(
+    mut commands: Commands,
+    player_state: Res<PlayerState>,
+    config: Res<GameConfig>,
+    game_textures: Res<GameTextures>,
+    app_state: Res<State<AppState>>,
+    rows: Query<(Entity, Option<&Children>), With<LifeIcons>>,
+) {
+    if !player_state.is_changed() {
+        return;
+    }
+
+    let lives = player_state.lives as usize;
+    let (w, h) = config.player.size;
+    for (row, children) in rows.iter() {
+        if children.map_or(0, |children| children.len()) == lives {
+            continue;
+        }
+
+        commands.entity(row).despawn_descendants();
+        commands.entity(row).with_children(|parent| {
+            for _ in 0..lives {
+                parent
+                    .spawn_bundle(ImageBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(w * ICON_SCALE), Val::Px(h * ICON_SCALE)),
+                            margin: UiRect::all(Val::Px(3.0)),
+                            ..default()
+                        },
+                        image: game_textures.player.clone().into(),
+                        visibility: Visibility { is_visible: in_game(&app_state) },
+                        ..default()
+                    })
+                    .insert(StatusHud);
+            }
+        });
+    }
+}
+
+/// Shows the health bar while a boss is up, filled to the share of hit points it has left.
+fn boss_bar_system(
+    app_state: Res<State<AppState>>,
+    boss_query: Query<&Health, With<Boss>>,
+    mut bar_query: Query<&mut Visibility, With<BossBar>>,
+    mut fill_query: Query<&mut Style, With<BossBarFill>>,
+) {
+    let health = boss_query.iter().next();
+    let visible = in_game(&app_state) && health.is_some();
+    for mut visibility in bar_query.iter_mut() {
+        if visibility.is_visible != visible {
+            visibility.is_visible = visible;
+        }
+    }
+
+    if let Some(health) = health {
+        let width = Val::Percent(100. * health.current as f32 / health.max as f32);
+        for mut style in fill_query.iter_mut() {
+            if style.size.width != width {
+                style.size.width = width;
+            }
+        }
+    }
+}
+
+fn wave_banner_system(
+    wave: Res<CurrentWave>,
+    app_state: Res<State<AppState>>,
+    mut query: Query<(&mut Text, &mut Visibility), With<WaveBanner>>,
+) {
+    if !wave.is_changed() && !app_state.is_changed() {
+        return;
+    }
+
+    for (mut text, mut visibility) in query.iter_mut() {
+        visibility.is_visible = in_game(&app_state) && wave.phase == WavePhase::Intermission;
+        let value = format!("Wave {}", wave.number);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn floating_text_spawn_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut awarded_events: EventReader<ScoreAwarded>,
+) {
+    for event in awarded_events.iter() {
+        let text = if event.multiplier > 1 {
+            format!("+{} ×{}", event.points, event.multiplier)
+        } else {
+            format!("+{}", event.points)
+        };
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 24.0,
+                        color: Color::GOLD,
+                    },
+                )
+                .with_alignment(TextAlignment::CENTER),
+                transform: Transform::from_xyz(event.position.x, event.position.y, 20.),
+                ..default()
+            })
+            .insert(FloatingText(Timer::from_seconds(FLOATING_TEXT_SECS, false)));
+    }
+}
+
+fn floating_text_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut FloatingText, &mut Transform, &mut Text)>,
+) {
+    for (entity, mut floating, mut transform, mut text) in query.iter_mut() {
+        floating.0.tick(time.delta());
+        if floating.0.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += FLOATING_TEXT_SPEED * time.delta_seconds();
+        text.sections[0].style.color.set_a(1. - floating.0.percent());
+    }
+}
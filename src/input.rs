@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::replay::Replay;
+use crate::timestep::FixedUpdateStage;
+
+/// Keys read by the player systems, in bit order of `TickInput` masks.
+pub const PLAYER_KEYS: [KeyCode; 5] = [
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Space,
+];
+
+/// Label of the systems that fill `TickInput`; player systems run after it.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputSource;
+
+/// Player keys as seen by the current fixed tick.
+///
+/// Presses are latched between ticks, so a tap shorter than a tick still registers and a
+/// frame without a tick doesn't swallow it.
+#[derive(Default)]
+pub struct TickInput {
+    held: u8,
+    previous: u8,
+    latched: u8,
+}
+
+impl TickInput {
+    pub fn mask(&self) -> u8 {
+        self.held
+    }
+
+    /// Advances to the next tick with `mask` as the held keys.
+    pub fn set_mask(&mut self, mask: u8) {
+        self.previous = self.held;
+        self.held = mask;
+    }
+
+    pub fn pressed(&self, key: KeyCode) -> bool {
+        key_bit(key).map_or(false, |bit| self.held & bit != 0)
+    }
+
+    pub fn just_pressed(&self, key: KeyCode) -> bool {
+        key_bit(key).map_or(false, |bit| self.held & !self.previous & bit != 0)
+    }
+}
+
+fn key_bit(key: KeyCode) -> Option<u8> {
+    PLAYER_KEYS.iter().position(|k| *k == key).map(|bit| 1 << bit)
+}
+
+fn key_mask(keys: impl Iterator<Item = KeyCode>) -> u8 {
+    keys.filter_map(key_bit).fold(0, |mask, bit| mask | bit)
+}
+
+pub struct TickInputPlugin;
+
+impl Plugin for TickInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TickInput>()
+            .add_system(latch_input_system)
+            .add_system_to_stage(FixedUpdateStage, sample_input_system.label(InputSource));
+    }
+}
+
+fn latch_input_system(kb: Res<Input<KeyCode>>, mut tick_input: ResMut<TickInput>) {
+    tick_input.latched |= key_mask(kb.get_just_pressed().copied());
+}
+
+fn sample_input_system(
+    kb: Res<Input<KeyCode>>,
+    replay: Option<Res<Replay>>,
+    mut tick_input: ResMut<TickInput>,
+) {
+    if replay.is_some() {
+        return;
+    }
+    let mask = key_mask(kb.get_pressed().copied()) | tick_input.latched;
+    tick_input.latched = 0;
+    tick_input.set_mask(mask);
+}
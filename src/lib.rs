@@ -0,0 +1,458 @@
+use bevy::utils::{HashMap, HashSet};
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+
+use crate::broadphase::{spatial_grid_system, Broadphase, Movement, SpatialGrid};
+use crate::collision::collision_system;
+use crate::combo::ComboPlugin;
+use crate::pickup::PickupPlugin;
+use crate::config::{GameConfig, PlayerConfig};
+use crate::highscore::HighScores;
+use crate::components::{Boss, BossPart, Damage, EnemyKind, Health, HitFlash, Invulnerable, Pickup, PickupKind, PowerUps, FromEnemy, Explosion, ExplosionScale, ExplosionTimer, ExplosionToSpawn, Laser, FromPlayer, Enemy, Movable, Velocity, Player};
+use player::PlayerPlugin;
+use enemy::EnemyPlugin;
+use events::{Collision, CollisionDetection, CollisionResponse, EnemyDestroyed, GameEventsPlugin, LaserImpact, PlayerDied, PlayerHit};
+use input::TickInputPlugin;
+use replay::ReplayPlugin;
+use rng::GameRng;
+use timestep::{FixedUpdateStage, TimestepPlugin};
+use wave::WavePlugin;
+
+pub mod broadphase;
+pub mod collision;
+pub mod combo;
+pub mod components;
+pub mod config;
+mod enemy;
+pub mod events;
+pub mod highscore;
+pub mod hud;
+pub mod input;
+pub mod menu;
+pub mod path;
+pub mod pickup;
+mod player;
+pub mod replay;
+pub mod rng;
+pub mod sound;
+pub mod timestep;
+pub mod wave;
+
+pub const TIME_STEP: f32 = 1. / 60.;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AppState {
+    MainMenu,
+    Playing,
+    Paused,
+    /// Asking for initials after a run that made the high-score table, before `GameOver`.
+    HighScoreEntry,
+    GameOver,
+}
+
+/// Run criteria for simulation systems in `FixedUpdateStage`, where `SystemSet::on_update`
+/// can't be ordered after the state driver. Also stops ticking as soon as the run has
+/// ended, rather than when the `GameOver` transition is applied on the next frame.
+pub fn playing_criteria(state: Res<State<AppState>>, run_ended: Res<RunEnded>) -> ShouldRun {
+    if state.current() == &AppState::Playing && !run_ended.0 {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+/// Label of the system applying `PlayerHit` events and sending `PlayerDied`.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct PlayerHealth;
+
+/// Set by the simulation when the player is out of lives, alongside the `GameOver` transition.
+#[derive(Default)]
+pub struct RunEnded(pub bool);
+
+pub struct WinSize {
+    pub w: f32,
+    pub h: f32
+}
+
+/// Sprite handles used when spawning entities. Headless runs keep the default (empty)
+/// handles; the windowed client replaces them with loaded assets at startup.
+#[derive(Default)]
+pub struct GameTextures {
+    pub player: Handle<Image>,
+    pub player_laser: Handle<Image>,
+    /// Ship and laser sprites by enemy kind or boss name.
+    pub enemies: HashMap<String, Handle<Image>>,
+    pub enemy_lasers: HashMap<String, Handle<Image>>,
+    pub explosion: Handle<TextureAtlas>
+}
+
+pub struct EnemyCount(pub u32);
+
+pub struct PlayerState {
+    pub on: bool,
+    /// Hull points of the current ship.
+    pub health: i64,
+    /// Ships left, including the current one.
+    pub lives: u32,
+    /// Time until the next ship comes in, once one was lost; unset before the first one.
+    pub respawn: Option<Timer>,
+    pub score: i64,
+}
+
+impl PlayerState {
+    /// State at the start of a run.
+    pub fn new(config: &PlayerConfig) -> Self {
+        Self {
+            on: false,
+            respawn: None,
+            health: config.health as i64,
+            lives: config.lives,
+            score: 0,
+        }
+    }
+
+    /// Applies a hit; losing the last hull point costs a life, and the next ship comes in
+    /// `respawn_delay` seconds later.
+    pub fn shot(&mut self, damage: u32, respawn_delay: f32) {
+        self.health -= damage as i64;
+        if self.health <= 0 {
+            self.on = false;
+            self.lives = self.lives.saturating_sub(1);
+            self.respawn = Some(Timer::from_seconds(respawn_delay, false));
+        }
+    }
+
+    pub fn spawned(&mut self, health: u32) {
+        self.on = true;
+        self.health = health as i64;
+    }
+}
+
+impl FromWorld for PlayerState {
+    fn from_world(world: &mut World) -> Self {
+        PlayerState::new(&world.resource::<GameConfig>().player)
+    }
+}
+
+/// All gameplay simulation, independent of windowing and rendering.
+///
+/// Expects a `WinSize` resource to be inserted by the caller; runs under `MinimalPlugins`.
+/// A `GameRng` inserted beforehand fixes the seed, otherwise a random one is picked, and a
+/// `FixedTime` inserted beforehand replaces the real-time clock (e.g. lockstep for tests).
+/// Tuning comes from a `GameConfig` resource, falling back to the built-in defaults.
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .init_resource::<GameTextures>()
+            .init_resource::<GameRng>()
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(EnemyCount(0))
+            .init_resource::<RunEnded>()
+            .init_resource::<SpatialGrid>()
+            .init_resource::<HighScores>()
+            .add_state(AppState::MainMenu)
+            .add_startup_system(log_seed_system)
+            .add_plugin(TimestepPlugin)
+            .add_plugin(GameEventsPlugin)
+            .add_plugin(TickInputPlugin)
+            .add_plugin(ReplayPlugin)
+            .add_plugin(PlayerPlugin)
+            .add_plugin(WavePlugin)
+            .add_plugin(EnemyPlugin)
+            .add_plugin(ComboPlugin)
+            .add_plugin(PickupPlugin)
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                SystemSet::new()
+                    .with_run_criteria(playing_criteria)
+                    .with_system(movable_system.label(Movement))
+                    .with_system(spatial_grid_system.label(Broadphase).after(Movement))
+                    .with_system(collision_system.label(CollisionDetection).after(Broadphase))
+                    .with_system(player_laser_hit_enemy_system.label(CollisionResponse).after(CollisionDetection))
+                    .with_system(enemy_laser_hit_player_system.label(CollisionResponse).after(CollisionDetection))
+                    .with_system(enemy_count_system.after(CollisionResponse))
+                    .with_system(explosion_on_death_system.after(PlayerHealth))
+                    .with_system(player_health_system.label(PlayerHealth).after(CollisionResponse))
+                    .with_system(run_end_system.after(PlayerHealth))
+                    .with_system(explosion_to_spawn_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Playing)
+                    .with_system(explosion_animation_system)
+                    .with_system(hit_flash_system),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::GameOver).with_system(reset_game_system));
+    }
+}
+
+fn log_seed_system(rng: Res<GameRng>) {
+    info!("game seed: {}", rng.seed());
+}
+
+fn movable_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    win_size: Res<WinSize>,
+    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable)>) {
+    for (entity, velocity, mut transform, movable) in query.iter_mut() {
+        let translation = &mut transform.translation;
+        translation.x += velocity.x * TIME_STEP * config.base_speed;
+        translation.y += velocity.y * TIME_STEP * config.base_speed;
+
+        if movable.auto_despawn {
+            const MARGIN: f32 = 200.;
+            if translation.y > win_size.h / 2. + MARGIN
+             || translation.y < -win_size.h / 2. - MARGIN
+             || translation.x > win_size.w / 2. + MARGIN
+             || translation.x < -win_size.w / 2. - MARGIN 
+            {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+
+/// Takes player lasers off whatever they hit first: an enemy, or a boss through any of its
+/// `BossPart`s. Each laser deals its damage once.
+fn player_laser_hit_enemy_system(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut destroyed_events: EventWriter<EnemyDestroyed>,
+    mut impact_events: EventWriter<LaserImpact>,
+    laser_query: Query<(&Transform, &Damage), (With<Laser>, With<FromPlayer>)>,
+    part_query: Query<&BossPart>,
+    mut enemy_query: Query<(&Transform, Option<&EnemyKind>, Option<&Boss>, &mut Health), Or<(With<Enemy>, With<Boss>)>>
+) {
+
+    let mut despawned_entities: HashSet<Entity> = HashSet::new();
+
+    for collision in collisions.iter() {
+        let (laser_entity, hit_entity) = match collision.pair(|entity| laser_query.contains(entity)) {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let enemy_entity = part_query.get(hit_entity).map_or(hit_entity, |part| part.boss);
+        if despawned_entities.contains(&laser_entity)
+            || despawned_entities.contains(&enemy_entity) {
+            continue;
+        }
+
+        let (laser_tf, damage) = laser_query.get(laser_entity).unwrap();
+        let (enemy_tf, enemy_kind, boss, mut health) = match enemy_query.get_mut(enemy_entity) {
+            Ok(enemy) => enemy,
+            Err(_) => continue,
+        };
+        let (kind, score) = match (enemy_kind, boss) {
+            (Some(enemy_kind), _) => (&enemy_kind.name, enemy_kind.score),
+            (None, Some(boss)) => (&boss.name, boss.score),
+            (None, None) => continue,
+        };
+
+        commands.entity(laser_entity).despawn();
+        despawned_entities.insert(laser_entity);
+        impact_events.send(LaserImpact {
+            position: laser_tf.translation,
+            from_player: true,
+        });
+
+        if health.hit(damage.0) {
+            commands.entity(enemy_entity).despawn();
+            despawned_entities.insert(enemy_entity);
+            destroyed_events.send(EnemyDestroyed {
+                enemy: enemy_entity,
+                kind: kind.clone(),
+                score,
+                position: enemy_tf.translation,
+            });
+        } else {
+            commands.entity(enemy_entity).insert(HitFlash::default());
+        }
+    }
+}
+
+fn enemy_count_system(
+    mut enemy_count: ResMut<EnemyCount>,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+) {
+    for _ in destroyed_events.iter() {
+        enemy_count.0 -= 1;
+    }
+}
+
+fn explosion_on_death_system(
+    mut commands: Commands,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+    mut died_events: EventReader<PlayerDied>,
+) {
+    for event in destroyed_events.iter() {
+        commands.spawn().insert(ExplosionToSpawn(event.position));
+    }
+    for event in died_events.iter() {
+        commands.spawn().insert(ExplosionToSpawn(event.position));
+    }
+}
+
+fn explosion_to_spawn_system(
+    mut commands: Commands,
+    game_textures: Res<GameTextures>,
+    query: Query<(Entity, &ExplosionToSpawn, Option<&ExplosionScale>)>,
+) {
+    for (explosion_spawn_entity, explosion_to_spawn, scale) in query.iter() {
+        let scale = scale.map_or(1., |scale| scale.0);
+        commands.spawn_bundle(SpriteSheetBundle {
+            texture_atlas: game_textures.explosion.clone(),
+            transform: Transform {
+                translation: explosion_to_spawn.0,
+                scale: Vec3::new(scale, scale, 1.),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Explosion)
+        .insert(ExplosionTimer::default());
+        commands.entity(explosion_spawn_entity).despawn();
+    }
+}
+
+fn explosion_animation_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>,
+) 
+{
+    for (entity, mut timer, mut sprite) in query.iter_mut() {
+        timer.0.tick(time.delta());
+        if timer.0.finished() {
+            sprite.index += 1;
+            if sprite.index >= config.explosion.len() {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+/// Fades enemies that survived a hit for the length of their `HitFlash`.
+fn hit_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut HitFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in query.iter_mut() {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            sprite.color.set_a(1.);
+            commands.entity(entity).remove::<HitFlash>();
+        } else {
+            sprite.color.set_a(0.35);
+        }
+    }
+}
+
+/// Hands the first enemy laser touching the player this tick to `player_health_system`.
+/// Lasers pass through an `Invulnerable` player and break harmlessly on a shielded one.
+fn enemy_laser_hit_player_system(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut hit_events: EventWriter<PlayerHit>,
+    mut impact_events: EventWriter<LaserImpact>,
+    laser_query: Query<(&Transform, &Damage), (With<Laser>, With<FromEnemy>)>,
+    player_query: Query<(&Transform, &PowerUps), (With<Player>, Without<Invulnerable>)>,
+) {
+    // Reads every collision either way, so later ones aren't left over for the next tick.
+    let mut hit = false;
+    for collision in collisions.iter() {
+        if hit {
+            continue;
+        }
+        let (player_entity, laser_entity) = match collision.pair(|entity| player_query.contains(entity)) {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let (laser_tf, damage) = match laser_query.get(laser_entity) {
+            Ok(laser) => laser,
+            Err(_) => continue,
+        };
+
+        let (player_tf, power_ups) = player_query.get(player_entity).unwrap();
+        if !power_ups.active(PickupKind::Shield) {
+            hit_events.send(PlayerHit {
+                player: player_entity,
+                damage: damage.0,
+                position: player_tf.translation,
+            });
+            hit = true;
+        }
+        commands.entity(laser_entity).despawn();
+        impact_events.send(LaserImpact {
+            position: laser_tf.translation,
+            from_player: false,
+        });
+    }
+}
+
+/// Applies hits to the player and takes the ship down when its hull is gone.
+fn player_health_system(
+    mut commands: Commands,
+    mut player_state: ResMut<PlayerState>,
+    config: Res<GameConfig>,
+    mut hit_events: EventReader<PlayerHit>,
+    mut died_events: EventWriter<PlayerDied>,
+) {
+    for hit in hit_events.iter() {
+        if !player_state.on {
+            continue;
+        }
+        player_state.shot(hit.damage, config.player_respawn_delay as f32);
+        if player_state.on == false {
+            commands.entity(hit.player).despawn();
+            died_events.send(PlayerDied {
+                player: hit.player,
+                position: hit.position,
+            });
+        }
+    }
+}
+
+/// Ends the run once the last ship is lost, going to initials entry if the score made the
+/// high-score table.
+fn run_end_system(
+    player_state: Res<PlayerState>,
+    mut app_state: ResMut<State<AppState>>,
+    mut run_ended: ResMut<RunEnded>,
+    high_scores: Res<HighScores>,
+    mut died_events: EventReader<PlayerDied>,
+) {
+    if died_events.iter().count() == 0 || player_state.lives > 0 {
+        return;
+    }
+    run_ended.0 = true;
+    let next = if high_scores.qualifies(player_state.score) {
+        AppState::HighScoreEntry
+    } else {
+        AppState::GameOver
+    };
+    let _ = app_state.set(next);
+}
+
+/// Clears everything left over from the previous run so the next one starts fresh.
+fn reset_game_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut player_state: ResMut<PlayerState>,
+    mut enemy_count: ResMut<EnemyCount>,
+    mut rng: ResMut<GameRng>,
+    mut run_ended: ResMut<RunEnded>,
+    query: Query<Entity, Or<(With<Enemy>, With<Laser>, With<Explosion>, With<ExplosionToSpawn>, With<Player>, With<Pickup>)>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+    *player_state = PlayerState::new(&config.player);
+    enemy_count.0 = 0;
+    rng.reset();
+    run_ended.0 = false;
+}
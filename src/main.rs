@@ -1,93 +1,57 @@
-use crate::components::ScoreText;
-use bevy::utils::HashSet;
-use bevy::math::Vec3Swizzles;
-use bevy::sprite::collide_aabb::collide;
 use bevy::diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin};
+use bevy::asset::AssetServerSettings;
 use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use invaders::components::{Boss, EnemyKind, FpsText, ScoreText};
+use invaders::config::{ConfigReloadPlugin, GameConfig, CONFIG_PATH};
+use invaders::events::EnemyDestroyed;
+use invaders::highscore::{self, HighScores};
+use invaders::hud::HudPlugin;
+use invaders::menu::MenuPlugin;
+use invaders::replay::{InputRecording, Recorder, Replay};
+use invaders::rng::GameRng;
+use invaders::sound::SoundPlugin;
+use invaders::timestep::interpolate_transforms_system;
+use invaders::{AppState, GameTextures, GameplayPlugin, PlayerState, WinSize};
 
-use crate::components::{FpsText, FromEnemy, Explosion, ExplosionTimer, ExplosionToSpawn, SpriteSize, Laser, FromPlayer, Enemy, Movable, Velocity, Player};
-use player::PlayerPlugin;
-use enemy::EnemyPlugin;
-
-mod components;
-mod enemy;
-mod player;
-
-
-const PLAYER_SPRITE: &str = "player_a_01.png";
-const PLAYER_SIZE: (f32, f32) = (144., 75.);
-const PLAYER_LASER_SPRITE: &str = "laser_a_01.png";
-const PLAYER_LASER_SIZE: (f32, f32) = (9., 54.);
-
-const ENEMY_SPRITE: &str = "enemy_a_01.png";
-const ENEMY_SIZE: (f32, f32) = (144., 75.);
-const ENEMEY_LASER_SPRITE: &str = "laser_b_01.png";
-const ENEMEY_LASER_SIZE: (f32, f32) = (17., 55.);
-
-const EXPLOSION_SHEET: &str = "explo_a_sheet.png";
-const EXPLOSION_LEN: usize = 16;
-
-const ENEMY_MAX: u32 = 4;
-const FORMATION_MEMBERS_MAX: u32 = 2;
-
-const PLAYER_RESPAWN_DELAY: f64 = 2.0;
-const SPRITE_SCALE: f32 = 0.5;
-const TIME_STEP: f32 = 1. / 60.;
-const BASE_SPEED: f32 = 500.;
-
-pub struct WinSize {
-    pub w: f32,
-    pub h: f32
-}
-
-struct GameTextures {
-    player: Handle<Image>,
-    player_laser: Handle<Image>,
-    enemy: Handle<Image>,
-    enemy_laser: Handle<Image>,
-    explosion: Handle<TextureAtlas>
-}
-
-struct EnemyCount(u32);
-
-struct PlayerState {
-    on: bool,
-    health: i64,
-    last_shot: f64,
-    score: i64,
-}
-impl Default for PlayerState {
-    fn default() -> Self {
-        Self {
-            on: false,
-            last_shot: -1.,
-            health: 3,
-            score: 0,
-        }
-    }
-}
+fn main() {
+    let config_path = arg_value("--config").unwrap_or_else(|| CONFIG_PATH.to_string());
+    let config = GameConfig::load(&config_path).unwrap_or_else(|err| {
+        eprintln!("{config_path}: {err}");
+        std::process::exit(1);
+    });
 
-impl PlayerState {
-    pub fn shot(&mut self, time: f64) {
-        self.health -= 1;
-        self.last_shot = time;
-        if self.health <= 0 {
-            self.on = false;        
-            self.score = 0;
-        }
-    }
+    let replay = arg_value("--replay").map(|path| {
+        InputRecording::load(&path)
+            .unwrap_or_else(|err| panic!("failed to load replay {path}: {err}"))
+    });
+    let rng = match &replay {
+        Some(recording) => GameRng::new(recording.seed),
+        None => match GameRng::seed_from_env() {
+            Ok(seed) => seed.map(GameRng::new).unwrap_or_default(),
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        },
+    };
 
-    pub fn spawned(&mut self) {
-        self.on = true;
-        self.last_shot = -1.;
-        self.health = 3;
-        self.score = 0;
+    let mut app = App::new();
+    if let Some(recording) = replay {
+        app.insert_resource(Replay::new(recording))
+            .add_startup_system(start_replay_system);
+    } else if let Some(path) = arg_value("--record") {
+        app.insert_resource(Recorder::new(rng.seed(), Some(path.into())));
     }
-}
 
+    let config_asset = config_path.strip_prefix("assets/").map(str::to_string);
 
-fn main() {
-    App::new()
+    app
+        .insert_resource(AssetServerSettings {
+            watch_for_changes: true,
+            ..default()
+        })
         .insert_resource(ClearColor(Color::rgb(0.04, 0.04, 0.04)))
         .insert_resource(WindowDescriptor {
             title: "Rust Invaders!".to_string(),
@@ -97,21 +61,42 @@ fn main() {
         })
     .add_plugins(DefaultPlugins)
     .add_plugin(FrameTimeDiagnosticsPlugin::default())
-    .add_plugin(PlayerPlugin)
-    .add_plugin(EnemyPlugin)
+    .insert_resource(rng)
+    .insert_resource(config)
+    .insert_resource(HighScores::load(highscore::default_path()))
+    .add_plugin(GameplayPlugin)
+    .add_plugin(MenuPlugin)
+    .add_plugin(HudPlugin)
+    .add_plugin(SoundPlugin)
     .add_startup_system(setup_system)
-    .add_system(movable_system)
-    .add_system(player_laser_hit_enemy_system)
-    .add_system(enemy_laser_hit_player_system)
-    .add_system(explosion_to_spawn_system)
-    .add_system(explosion_animation_system)
+    .add_system(texture_reload_system)
+    .add_system_to_stage(
+        CoreStage::PostUpdate,
+        interpolate_transforms_system.before(TransformSystem::TransformPropagate),
+    )
     .add_system(fps_update_system)
-    .add_system(score_update_system)
-    .run();
+    .add_system(score_update_system);
+
+    // Only a config inside the asset folder can be watched by the asset server.
+    if let Some(asset_path) = config_asset {
+        app.add_plugin(ConfigReloadPlugin { asset_path });
+    }
+
+    app.run();
+}
+
+/// Value following `name` on the command line, e.g. `--replay run.orpl`.
+fn arg_value(name: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != name).nth(1)
+}
+
+fn start_replay_system(mut app_state: ResMut<State<AppState>>) {
+    let _ = app_state.set(AppState::Playing);
 }
 
 fn setup_system(mut commands: Commands, 
     asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut windows: ResMut<Windows>) {
     commands.spawn_bundle(Camera2dBundle::default());
@@ -125,19 +110,8 @@ fn setup_system(mut commands: Commands,
     let win_size = WinSize { w: win_h, h: win_h };
     commands.insert_resource(win_size);
 
-    let texture_handle = asset_server.load(EXPLOSION_SHEET);
-    let texture_atlas = TextureAtlas::from_grid(texture_handle, Vec2::new(64., 64.), 4, 4);
-    let explosion = texture_atlases.add(texture_atlas);
-
-    let game_textures = GameTextures {
-        player: asset_server.load(PLAYER_SPRITE),
-        player_laser: asset_server.load(PLAYER_LASER_SPRITE),
-        enemy: asset_server.load(ENEMY_SPRITE),
-        enemy_laser: asset_server.load(ENEMEY_LASER_SPRITE),
-        explosion,
-    };
+    let game_textures = load_game_textures(&asset_server, &mut texture_atlases, &config);
     commands.insert_resource(game_textures);
-    commands.insert_resource(EnemyCount(0));
     commands
         .spawn_bundle(NodeBundle {
             style: Style {
@@ -219,143 +193,84 @@ fn setup_system(mut commands: Commands,
     });
 }
 
-fn movable_system(
-    mut commands: Commands,
-    win_size: Res<WinSize>,
-    mut query: Query<(Entity, &Velocity, &mut Transform, &Movable)>) {
-    for (entity, velocity, mut transform, movable) in query.iter_mut() {
-        let translation = &mut transform.translation;
-        translation.x += velocity.x * TIME_STEP * BASE_SPEED;
-        translation.y += velocity.y * TIME_STEP * BASE_SPEED;
-
-        if movable.auto_despawn {
-            const MARGIN: f32 = 200.;
-            if translation.y > win_size.h / 2. + MARGIN
-             || translation.y < -win_size.h / 2. - MARGIN
-             || translation.x > win_size.w / 2. + MARGIN
-             || translation.x < -win_size.w / 2. - MARGIN 
-            {
-                commands.entity(entity).despawn();
-            }
-        }
+fn load_game_textures(
+    asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    config: &GameConfig,
+) -> GameTextures {
+    let explosion_config = &config.explosion;
+    let texture_handle = asset_server.load(explosion_config.sheet.as_str());
+    let texture_atlas = TextureAtlas::from_grid(
+        texture_handle,
+        Vec2::from(explosion_config.tile_size),
+        explosion_config.columns,
+        explosion_config.rows,
+    );
+
+    GameTextures {
+        player: asset_server.load(config.player.sprite.as_str()),
+        player_laser: asset_server.load(config.player.laser_sprite.as_str()),
+        enemies: config
+            .enemies
+            .iter()
+            .map(|(name, kind)| (name.clone(), asset_server.load(kind.sprite.as_str())))
+            .chain(config.bosses.iter().map(|(name, boss)| (name.clone(), asset_server.load(boss.sprite.as_str()))))
+            .collect(),
+        enemy_lasers: config
+            .enemies
+            .iter()
+            .map(|(name, kind)| (name.clone(), asset_server.load(kind.laser_sprite.as_str())))
+            .chain(config.bosses.iter().map(|(name, boss)| (name.clone(), asset_server.load(boss.laser_sprite.as_str()))))
+            .collect(),
+        explosion: texture_atlases.add(texture_atlas),
     }
 }
 
-
-fn player_laser_hit_enemy_system(
-    mut commands: Commands,
-    mut player_state: ResMut<PlayerState>,
-    mut enemy_count: ResMut<EnemyCount>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromPlayer>)>,
-    enemy_query: Query<(Entity, &Transform, &SpriteSize), With<Enemy>>
+/// Picks up sprite paths changed in the config and swaps them on sprites already spawned.
+/// Edits to the image files themselves are reloaded by the asset server on its own.
+fn texture_reload_system(
+    config: Res<GameConfig>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut game_textures: ResMut<GameTextures>,
+    mut sprites: Query<(&mut Handle<Image>, Option<&EnemyKind>, Option<&Boss>)>,
+    mut sheets: Query<&mut Handle<TextureAtlas>>,
 ) {
-
-    let mut despawned_entities: HashSet<Entity> = HashSet::new();
-
-    for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-
-        if despawned_entities.contains(&laser_entity) {
-            continue;
-        }
-
-        let laser_scale = Vec2::from(laser_tf.scale.xy());
-
-        for (enemy_entity, enemy_tf, enemy_size) in enemy_query.iter() {
-            if despawned_entities.contains(&laser_entity) 
-                || despawned_entities.contains(&enemy_entity){
-                continue;
-            }
-
-
-            let enemy_scale = Vec2::from(enemy_tf.scale.xy());
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                enemy_tf.translation,
-                enemy_size.0 * enemy_scale,
-            );
-
-            if let Some(_) = collision {
-                player_state.score += 1;
-                commands.entity(enemy_entity).despawn();
-                despawned_entities.insert(enemy_entity);
-                enemy_count.0 -= 1;
-                commands.entity(laser_entity).despawn();
-                despawned_entities.insert(laser_entity);
-                commands.spawn().insert(ExplosionToSpawn(enemy_tf.translation.clone()));
-            }
-        }
+    if !config.is_changed() || config.is_added() {
+        return;
     }
-}
 
-fn explosion_to_spawn_system(
-    mut commands: Commands,
-    game_textures: Res<GameTextures>,
-    query: Query<(Entity, &ExplosionToSpawn)>,
-) {
-    for (explosion_spawn_entity, explosion_to_spawn) in query.iter() {
-        commands.spawn_bundle(SpriteSheetBundle {
-            texture_atlas: game_textures.explosion.clone(),
-            transform: Transform {
-                translation: explosion_to_spawn.0,
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(Explosion)
-        .insert(ExplosionTimer::default());
-        commands.entity(explosion_spawn_entity).despawn();
+    let reloaded = load_game_textures(&asset_server, &mut texture_atlases, &config);
+    // Enemy and boss ships are matched by name, since several of them may share one image.
+    let mut swaps = vec![
+        (&game_textures.player, &reloaded.player),
+        (&game_textures.player_laser, &reloaded.player_laser),
+    ];
+    for (name, old) in game_textures.enemy_lasers.iter() {
+        if let Some(new) = reloaded.enemy_lasers.get(name) {
+            swaps.push((old, new));
+        }
     }
-}
-
-fn explosion_animation_system(
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &mut ExplosionTimer, &mut TextureAtlasSprite), With<Explosion>>,
-) 
-{
-    for (entity, mut timer, mut sprite) in query.iter_mut() {
-        timer.0.tick(time.delta());
-        if timer.0.finished() {
-            sprite.index += 1;
-            if sprite.index >= EXPLOSION_LEN {
-                commands.entity(entity).despawn();
+    for (mut handle, kind, boss) in sprites.iter_mut() {
+        let name = kind.map(|kind| &kind.name).or_else(|| boss.map(|boss| &boss.name));
+        let new = match name {
+            Some(name) => reloaded.enemies.get(name),
+            None => swaps.iter().find(|(old, _)| *old == &*handle).map(|(_, new)| *new),
+        };
+        if let Some(new) = new {
+            if *handle != *new {
+                *handle = new.clone();
             }
         }
     }
-}
-
-fn enemy_laser_hit_player_system(
-    mut commands: Commands,
-    mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
-    laser_query: Query<(Entity, &Transform, &SpriteSize), (With<Laser>, With<FromEnemy>)>,
-    player_query: Query<(Entity, &Transform, &SpriteSize), With<Player>>,
-) {
-    if let Ok((player_entity, player_tf, player_size)) = player_query.get_single() {
-        let player_scale = Vec2::from(player_tf.scale.xy());
-        for (laser_entity, laser_tf, laser_size) in laser_query.iter() {
-            let laser_scale = Vec2::from(laser_tf.scale.xy());
-
-            let collision = collide(
-                laser_tf.translation,
-                laser_size.0 * laser_scale,
-                player_tf.translation,
-                player_size.0 * player_scale,
-            );
-
-            if let Some(_) = collision {
-                player_state.shot(time.seconds_since_startup());
-                if player_state.on == false {
-                    commands.entity(player_entity).despawn();
-                }
-                commands.entity(laser_entity).despawn();
-                break;
-            }
+    for mut handle in sheets.iter_mut() {
+        if *handle == game_textures.explosion {
+            *handle = reloaded.explosion.clone();
         }
     }
-}
 
+    *game_textures = reloaded;
+}
 
 fn fps_update_system(diagnostics: Res<Diagnostics>, mut query: Query<&mut Text, With<FpsText>>) {
     for mut text in &mut query {
@@ -367,10 +282,17 @@ fn fps_update_system(diagnostics: Res<Diagnostics>, mut query: Query<&mut Text,
     }
 }
 
+/// Refreshes the score when an enemy is destroyed, and when a run starts or ends.
 fn score_update_system(
-    player_state: ResMut<PlayerState>,
+    player_state: Res<PlayerState>,
+    app_state: Res<State<AppState>>,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
     mut query: Query<&mut Text, With<ScoreText>>
 ) {
+    if destroyed_events.iter().count() == 0 && !app_state.is_changed() {
+        return;
+    }
+
     let score = player_state.score;
     for mut text in &mut query {
         text.sections[1].value = format!("{score}");
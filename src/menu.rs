@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use crate::{AppState, PlayerState};
+use crate::components::MenuScreen;
+use crate::highscore::{today, HighScore, HighScores, INITIALS_LEN};
+use crate::wave::CurrentWave;
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingInitials>()
+            .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(main_menu_setup_system))
+            .add_system_set(SystemSet::on_update(AppState::MainMenu).with_system(main_menu_input_system))
+            .add_system_set(SystemSet::on_exit(AppState::MainMenu).with_system(despawn_screen_system))
+            .add_system_set(SystemSet::on_update(AppState::Playing).with_system(pause_input_system))
+            .add_system_set(SystemSet::on_enter(AppState::Paused).with_system(pause_setup_system))
+            .add_system_set(SystemSet::on_update(AppState::Paused).with_system(resume_input_system))
+            .add_system_set(SystemSet::on_exit(AppState::Paused).with_system(despawn_screen_system))
+            .add_system_set(SystemSet::on_enter(AppState::HighScoreEntry).with_system(initials_setup_system))
+            .add_system_set(SystemSet::on_update(AppState::HighScoreEntry).with_system(initials_input_system).with_system(initials_screen_system))
+            .add_system_set(SystemSet::on_exit(AppState::HighScoreEntry).with_system(despawn_screen_system))
+            .add_system_set(SystemSet::on_enter(AppState::GameOver).with_system(game_over_setup_system))
+            .add_system_set(SystemSet::on_update(AppState::GameOver).with_system(game_over_input_system))
+            .add_system_set(SystemSet::on_exit(AppState::GameOver).with_system(despawn_screen_system));
+    }
+}
+
+/// Spawns a centered column of text lines, tagged so it can be torn down on state exit.
+fn spawn_screen(commands: &mut Commands, asset_server: &AssetServer, lines: &[(String, f32, Color)]) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::rgba(0., 0., 0., 0.6).into(),
+            ..default()
+        })
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            for (value, font_size, color) in lines {
+                parent.spawn_bundle(
+                    TextBundle::from_section(
+                        value.clone(),
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                            font_size: *font_size,
+                            color: *color,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    }),
+                );
+            }
+        });
+}
+
+fn main_menu_setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(&mut commands, &asset_server, &[
+        ("ORION".to_string(), 80.0, Color::GOLD),
+        ("Press Enter to start".to_string(), 30.0, Color::WHITE),
+    ]);
+}
+
+fn main_menu_input_system(mut kb: ResMut<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Return) {
+        kb.reset(KeyCode::Return);
+        let _ = app_state.set(AppState::Playing);
+    }
+}
+
+fn pause_input_system(mut kb: ResMut<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        let _ = app_state.push(AppState::Paused);
+    }
+}
+
+fn pause_setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(&mut commands, &asset_server, &[
+        ("PAUSED".to_string(), 80.0, Color::WHITE),
+        ("Press Escape to resume".to_string(), 30.0, Color::WHITE),
+    ]);
+}
+
+fn resume_input_system(mut kb: ResMut<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        let _ = app_state.pop();
+    }
+}
+
+/// Initials typed so far on the high-score entry screen.
+#[derive(Default)]
+struct PendingInitials(String);
+
+fn spawn_initials_screen(commands: &mut Commands, asset_server: &AssetServer, score: i64, initials: &str) {
+    let slots: Vec<String> = (0..INITIALS_LEN)
+        .map(|i| initials.chars().nth(i).unwrap_or('_').to_string())
+        .collect();
+    spawn_screen(commands, asset_server, &[
+        ("NEW HIGH SCORE".to_string(), 70.0, Color::GOLD),
+        (format!("{score}"), 40.0, Color::GOLD),
+        ("Enter your initials".to_string(), 30.0, Color::WHITE),
+        (slots.join(" "), 60.0, Color::WHITE),
+        ("Press Enter to confirm".to_string(), 24.0, Color::WHITE),
+    ]);
+}
+
+fn initials_setup_system(mut initials: ResMut<PendingInitials>) {
+    initials.0.clear();
+}
+
+/// Redraws the high-score entry screen whenever the initials change, including when the
+/// screen comes up.
+fn initials_screen_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    initials: Res<PendingInitials>,
+    player_state: Res<PlayerState>,
+    screens: Query<Entity, With<MenuScreen>>,
+) {
+    if !initials.is_changed() {
+        return;
+    }
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_initials_screen(&mut commands, &asset_server, player_state.score, &initials.0);
+}
+
+fn initials_input_system(
+    mut kb: ResMut<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut initials: ResMut<PendingInitials>,
+    mut high_scores: ResMut<HighScores>,
+    mut app_state: ResMut<State<AppState>>,
+    player_state: Res<PlayerState>,
+    wave: Res<CurrentWave>,
+) {
+    for character in characters.iter() {
+        if character.char.is_ascii_alphanumeric() && initials.0.len() < INITIALS_LEN {
+            initials.0.push(character.char.to_ascii_uppercase());
+        }
+    }
+    if kb.just_pressed(KeyCode::Back) {
+        kb.reset(KeyCode::Back);
+        initials.0.pop();
+    }
+
+    if kb.just_pressed(KeyCode::Return) && !initials.0.is_empty() {
+        kb.reset(KeyCode::Return);
+        high_scores.insert(HighScore {
+            initials: initials.0.clone(),
+            score: player_state.score,
+            wave: wave.number,
+            date: today(),
+        });
+        if let Err(err) = high_scores.save() {
+            warn!("failed to save high scores: {err}");
+        }
+        let _ = app_state.set(AppState::GameOver);
+    }
+}
+
+fn game_over_setup_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_state: Res<PlayerState>,
+    high_scores: Res<HighScores>,
+) {
+    let mut lines = vec![
+        ("GAME OVER".to_string(), 80.0, Color::RED),
+        (format!("Final score: {}", player_state.score), 40.0, Color::GOLD),
+    ];
+    if !high_scores.entries.is_empty() {
+        lines.push(("High scores".to_string(), 30.0, Color::WHITE));
+    }
+    for (rank, entry) in high_scores.entries.iter().enumerate() {
+        lines.push((
+            format!("{}. {}  {}  wave {}  {}", rank + 1, entry.initials, entry.score, entry.wave, entry.date),
+            18.0,
+            Color::WHITE,
+        ));
+    }
+    lines.push(("Press Enter to restart, Escape for the menu".to_string(), 24.0, Color::WHITE));
+    spawn_screen(&mut commands, &asset_server, &lines);
+}
+
+fn game_over_input_system(mut kb: ResMut<Input<KeyCode>>, mut app_state: ResMut<State<AppState>>) {
+    if kb.just_pressed(KeyCode::Return) {
+        kb.reset(KeyCode::Return);
+        let _ = app_state.set(AppState::Playing);
+    } else if kb.just_pressed(KeyCode::Escape) {
+        kb.reset(KeyCode::Escape);
+        let _ = app_state.set(AppState::MainMenu);
+    }
+}
+
+fn despawn_screen_system(mut commands: Commands, query: Query<Entity, With<MenuScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
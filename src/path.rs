@@ -0,0 +1,185 @@
+use core::f32::consts::TAU;
+
+use bevy::math::Vec2;
+use serde::Deserialize;
+
+/// Line segments each curve is sampled into.
+const SAMPLES: usize = 16;
+
+/// A curve as authored in the config. Points are fractions of the half-window, so (±1, ±1)
+/// are the corners of the screen and anything beyond is off it.
+#[derive(Debug, Clone, Deserialize)]
+pub enum PathShape {
+    /// Straight lines from point to point.
+    Waypoints(Vec<(f32, f32)>),
+    /// Cubic Bezier curves: a start point, then two control points and an end point for
+    /// each curve.
+    Bezier(Vec<(f32, f32)>),
+    /// A smooth Catmull-Rom spline through every point.
+    CatmullRom(Vec<(f32, f32)>),
+    /// One counter-clockwise turn, starting from the rightmost point.
+    Ellipse { center: (f32, f32), radius: (f32, f32) },
+}
+
+impl PathShape {
+    /// What is wrong with the shape, if anything.
+    pub fn problem(&self) -> Option<&'static str> {
+        match self {
+            PathShape::Waypoints(points) | PathShape::CatmullRom(points) if points.is_empty() => {
+                Some("must have at least one point")
+            }
+            PathShape::Bezier(points) if points.len() < 4 || (points.len() - 1) % 3 != 0 => {
+                Some("must have a start point and three more per curve")
+            }
+            PathShape::Ellipse { radius, .. } if radius.0 <= 0. || radius.1 <= 0. => Some("radius must be positive"),
+            _ => None,
+        }
+    }
+
+    /// Points along the shape, in the shape's own coordinates.
+    pub fn sample(&self) -> Vec<Vec2> {
+        let vec = |(x, y): (f32, f32)| Vec2::new(x, y);
+        match self {
+            PathShape::Waypoints(points) => points.iter().copied().map(vec).collect(),
+            PathShape::Bezier(points) => {
+                let points: Vec<Vec2> = points.iter().copied().map(vec).collect();
+                let mut samples = vec![points[0]];
+                for curve in points[1..].chunks_exact(3) {
+                    let (p0, p1, p2, p3) = (*samples.last().unwrap(), curve[0], curve[1], curve[2]);
+                    samples.extend((1..=SAMPLES).map(|i| {
+                        let t = i as f32 / SAMPLES as f32;
+                        let u = 1. - t;
+                        p0 * u * u * u + p1 * 3. * u * u * t + p2 * 3. * u * t * t + p3 * t * t * t
+                    }));
+                }
+                samples
+            }
+            PathShape::CatmullRom(points) => {
+                let points: Vec<Vec2> = points.iter().copied().map(vec).collect();
+                let at = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+                let mut samples = vec![points[0]];
+                for i in 0..points.len() as isize - 1 {
+                    let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+                    samples.extend((1..=SAMPLES).map(|s| {
+                        let t = s as f32 / SAMPLES as f32;
+                        (p1 * 2.
+                            + (p2 - p0) * t
+                            + (p0 * 2. - p1 * 5. + p2 * 4. - p3) * t * t
+                            + (p1 * 3. - p0 - p2 * 3. + p3) * t * t * t)
+                            * 0.5
+                    }));
+                }
+                samples
+            }
+            PathShape::Ellipse { center, radius } => (0..=SAMPLES * 4)
+                .map(|i| {
+                    let angle = TAU * i as f32 / (SAMPLES * 4) as f32;
+                    vec(*center) + Vec2::new(radius.0 * angle.cos(), radius.1 * angle.sin())
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What enemies do once they reach the end of a path's entry.
+#[derive(Debug, Clone, Deserialize)]
+pub enum PathEnd {
+    /// Stay on the last point.
+    Hold,
+    /// Head back to the first point and go through the entry again.
+    Restart,
+    /// Go round the shape, closed back on itself, forever.
+    Loop(PathShape),
+}
+
+/// A path sampled into a polyline in world coordinates and walked by distance travelled.
+/// Past its end it holds on the last point, or wraps around to `loop_start`.
+#[derive(Debug, Clone)]
+pub struct Path {
+    points: Vec<Vec2>,
+    /// Distance along the path to each point.
+    distances: Vec<f32>,
+    loop_start: Option<f32>,
+}
+
+impl Path {
+    /// Builds the path of `entry` followed by `end`, scaled from path to world coordinates
+    /// by `scale` and flipped left to right if `mirror` is set.
+    pub fn new(entry: &PathShape, end: &PathEnd, scale: Vec2, mirror: bool) -> Self {
+        let flip = Vec2::new(if mirror { -1. } else { 1. }, 1.);
+        let world = |points: Vec<Vec2>| -> Vec<Vec2> { points.into_iter().map(|p| p * flip * scale).collect() };
+
+        let mut points = world(entry.sample());
+        let loop_start = match end {
+            PathEnd::Hold => None,
+            PathEnd::Restart => {
+                points.push(points[0]);
+                Some(0)
+            }
+            PathEnd::Loop(shape) => {
+                let start = points.len();
+                let shape = world(shape.sample());
+                points.push(shape[0]);
+                points.extend(shape.iter().skip(1));
+                points.push(shape[0]);
+                Some(start)
+            }
+        };
+
+        let mut path = Self::polyline(points);
+        path.loop_start = loop_start.map(|i| path.distances[i]);
+        path
+    }
+
+    /// Straight lines through `points`, holding on the last one.
+    pub fn polyline(points: Vec<Vec2>) -> Self {
+        let mut distances = Vec::with_capacity(points.len());
+        let mut total = 0.;
+        for (i, point) in points.iter().enumerate() {
+            if i > 0 {
+                total += point.distance(points[i - 1]);
+            }
+            distances.push(total);
+        }
+        Self { points, distances, loop_start: None }
+    }
+
+    pub fn start(&self) -> Vec2 {
+        self.points[0]
+    }
+
+    pub fn end(&self) -> Vec2 {
+        *self.points.last().unwrap()
+    }
+
+    pub fn length(&self) -> f32 {
+        *self.distances.last().unwrap()
+    }
+
+    /// Where the repeating part of a looping path starts, and its length.
+    pub fn looped_part(&self) -> Option<(f32, f32)> {
+        self.loop_start.map(|start| (start, self.length() - start))
+    }
+
+    /// Whether something `distance` along has reached the end of a path that holds there.
+    pub fn finished(&self, distance: f32) -> bool {
+        self.loop_start.is_none() && distance >= self.length()
+    }
+
+    /// Position `distance` along the path.
+    pub fn position(&self, distance: f32) -> Vec2 {
+        let length = self.length();
+        let distance = match self.loop_start {
+            Some(start) if distance > length && length > start => start + (distance - start) % (length - start),
+            _ => distance.clamp(0., length),
+        };
+
+        let i = self.distances.partition_point(|d| *d <= distance).clamp(1, self.points.len().max(2) - 1);
+        if i >= self.points.len() {
+            return self.points[0];
+        }
+        let (d0, d1) = (self.distances[i - 1], self.distances[i]);
+        let t = if d1 > d0 { (distance - d0) / (d1 - d0) } else { 0. };
+        self.points[i - 1].lerp(self.points[i], t)
+    }
+}
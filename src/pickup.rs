@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use rand::Rng;
+
+use crate::components::{layers, Collider, Interpolated, Movable, Pickup, PickupKind, Player, PowerUps, ShipStats, Velocity};
+use crate::config::GameConfig;
+use crate::events::{Collision, CollisionDetection, CollisionResponse, EnemyDestroyed, PickupCollected};
+use crate::rng::GameRng;
+use crate::timestep::FixedUpdateStage;
+use crate::{playing_criteria, PlayerState, TIME_STEP};
+
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct PowerUpTick;
+
+/// Pickups dropped by destroyed enemies, and the timed effects they grant the player.
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set_to_stage(
+            FixedUpdateStage,
+            SystemSet::new()
+                .with_run_criteria(playing_criteria)
+                // Last to draw from `GameRng` in a tick, after the enemy systems moving before
+                // collisions.
+                .with_system(pickup_drop_system.after(CollisionResponse))
+                .with_system(pickup_collect_system.label(CollisionResponse).after(CollisionDetection))
+                .with_system(power_up_tick_system.label(PowerUpTick).after(CollisionResponse))
+                .with_system(player_stats_system.after(PowerUpTick)),
+        )
+        .add_system(shield_tint_system);
+    }
+}
+
+fn pickup_drop_system(
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut destroyed_events: EventReader<EnemyDestroyed>,
+) {
+    let pickups = &config.pickups;
+    for event in destroyed_events.iter() {
+        if !rng.gen_bool(pickups.drop_chance) {
+            continue;
+        }
+
+        let kind = pickups.pick(&mut *rng);
+        let translation = Vec3::new(event.position.x, event.position.y, 5.);
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color: kind.color(),
+                    custom_size: Some(Vec2::from(pickups.size)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(translation),
+                ..Default::default()
+            })
+            .insert(Pickup(kind))
+            .insert(Collider::aabb(pickups.size, layers::PICKUP, layers::PLAYER))
+            .insert(Interpolated::from(translation))
+            .insert(Movable { auto_despawn: true })
+            .insert(Velocity { x: 0., y: -pickups.speed });
+    }
+}
+
+fn pickup_collect_system(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    mut player_state: ResMut<PlayerState>,
+    mut collisions: EventReader<Collision>,
+    mut collected_events: EventWriter<PickupCollected>,
+    pickup_query: Query<(&Pickup, &Transform)>,
+    mut player_query: Query<&mut PowerUps, With<Player>>,
+) {
+    let mut collected: HashSet<Entity> = HashSet::new();
+    for collision in collisions.iter() {
+        let (pickup_entity, player_entity) = match collision.pair(|entity| pickup_query.contains(entity)) {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let mut power_ups = match player_query.get_mut(player_entity) {
+            Ok(power_ups) => power_ups,
+            Err(_) => continue,
+        };
+        if !collected.insert(pickup_entity) {
+            continue;
+        }
+
+        let (pickup, pickup_tf) = pickup_query.get(pickup_entity).unwrap();
+        match pickup.0 {
+            PickupKind::ExtraLife => player_state.lives = (player_state.lives + 1).min(config.player.max_lives),
+            kind => power_ups.grant(kind, config.pickups.duration),
+        }
+        commands.entity(pickup_entity).despawn();
+        collected_events.send(PickupCollected {
+            kind: pickup.0,
+            position: pickup_tf.translation,
+        });
+    }
+}
+
+fn power_up_tick_system(mut query: Query<&mut PowerUps>) {
+    for mut power_ups in query.iter_mut() {
+        // Only an effect running out counts as a change, for the systems watching `PowerUps`.
+        if power_ups.bypass_change_detection().tick(Duration::from_secs_f32(TIME_STEP)) {
+            power_ups.set_changed();
+        }
+    }
+}
+
+/// Derives the ship's stats from the config and its running power-ups whenever either one
+/// changes. Runs at the end of the tick, so the next tick's input moves the ship with them.
+fn player_stats_system(
+    config: Res<GameConfig>,
+    mut query: Query<(&mut ShipStats, &PowerUps, ChangeTrackers<PowerUps>), With<Player>>,
+) {
+    for (mut ship_stats, power_ups, power_ups_tracker) in query.iter_mut() {
+        if !config.is_changed() && !power_ups_tracker.is_changed() {
+            continue;
+        }
+        let mut stats = config.player.stats.clone();
+        if power_ups.active(PickupKind::SpeedBoost) {
+            stats.max_speed *= config.pickups.speed_boost;
+            stats.accel_speed *= config.pickups.speed_boost;
+        }
+        if *ship_stats != stats {
+            *ship_stats = stats;
+        }
+    }
+}
+
+fn shield_tint_system(mut query: Query<(&PowerUps, &mut Sprite), (With<Player>, Changed<PowerUps>)>) {
+    for (power_ups, mut sprite) in query.iter_mut() {
+        let color = if power_ups.active(PickupKind::Shield) { Color::CYAN } else { Color::WHITE };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -1,93 +1,140 @@
+use std::time::Duration;
+
 use crate::components::ShipStats;
 use bevy::prelude::*;
-use bevy::time::FixedTimestep;
-use crate::{PlayerState, GameTextures, WinSize, PLAYER_RESPAWN_DELAY, PLAYER_SIZE, SPRITE_SCALE, PLAYER_LASER_SIZE};
-use crate::components::{FromPlayer, Movable, Player, SpriteSize, Velocity, Laser};
+use crate::config::GameConfig;
+use crate::{playing_criteria, PlayerState, GameTextures, WinSize, TIME_STEP};
+use crate::components::{layers, Collider, Damage, FromPlayer, Interpolated, Invulnerable, PickupKind, PowerUps, Movable, Player, SpriteSize, Velocity, Laser, Weapon};
+use crate::broadphase::Movement;
+use crate::input::{InputSource, TickInput};
+use crate::timestep::FixedUpdateStage;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(PlayerState::default())
-            .add_system_set(
+        app.init_resource::<PlayerState>()
+            .add_system_set_to_stage(
+                FixedUpdateStage,
                 SystemSet::new()
-                .with_run_criteria(FixedTimestep::step(0.5))
-                .with_system(player_spawn_system),
-            )
-            .add_system(player_keyboard_event_system )
-            .add_system(player_fire_system);
+                .with_run_criteria(playing_criteria)
+                .after(InputSource)
+                .with_system(player_spawn_system.before(Movement))
+                .with_system(player_keyboard_event_system.before(Movement))
+                .with_system(player_fire_system)
+                .with_system(player_invulnerability_system),
+            );
     }
 }
 
+/// Brings in a ship at the start of a run, and the next one once the respawn delay is over.
 fn player_spawn_system(
     mut commands: Commands,
     mut player_state: ResMut<PlayerState>,
-    time: Res<Time>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
     win_size: Res<WinSize>
 ) {
-    let now = time.seconds_since_startup();
-    let last_shot = player_state.last_shot;
+    if player_state.on || player_state.lives == 0 {
+        return;
+    }
+    let waiting = match player_state.respawn.as_mut() {
+        Some(timer) => !timer.tick(Duration::from_secs_f32(TIME_STEP)).finished(),
+        None => false,
+    };
+    if waiting {
+        return;
+    }
 
-    if !player_state.on && (last_shot == -1. || now > last_shot + PLAYER_RESPAWN_DELAY) {
-        let bottom = -win_size.h / 2.;
+    let bottom = -win_size.h / 2.;
+    let translation = Vec3::new(0., bottom + config.player.size.1 / 2. + 5., 10.);
 
-        commands.spawn_bundle(SpriteBundle {
-            texture: game_textures.player.clone(),
-            transform: Transform {
-                translation: Vec3::new(0., bottom + PLAYER_SIZE.1 / 2. + 5., 10.),
-                scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                ..Default::default()
-            },
+    let mut player = commands.spawn_bundle(SpriteBundle {
+        texture: game_textures.player.clone(),
+        transform: Transform {
+            translation,
+            scale: Vec3::new(config.sprite_scale, config.sprite_scale, 1.),
             ..Default::default()
-        })
-            .insert(Player)
-            .insert(SpriteSize::from(PLAYER_SIZE))
-            .insert(Movable{auto_despawn: false})
-            .insert(Velocity {x: 0., y: 0.})
-            .insert(ShipStats { accel_speed: 0.3, decel_speed: 10.0, max_speed: 0.7, laser_speed: 1.0 });
-
-        player_state.spawned();
+        },
+        ..Default::default()
+    });
+    player
+        .insert(Player)
+        .insert(Interpolated::from(translation))
+        .insert(SpriteSize::from(config.player.size))
+        .insert(Collider::aabb(config.player.size, layers::PLAYER, layers::ENEMY_LASER))
+        .insert(Movable{auto_despawn: false})
+        .insert(Velocity {x: 0., y: 0.})
+        .insert(config.player.stats.clone())
+        .insert(PowerUps::default())
+        .insert(Weapon::new(config.player.weapon.clone()));
+
+    if player_state.respawn.is_some() {
+        player.insert(Invulnerable(Timer::from_seconds(config.player.invulnerability, false)));
     }
 
+    player_state.spawned(config.player.health);
 }
 
+/// Fires a volley of the player's weapon when Space is pressed, or while it's held for
+/// weapons that autofire, once the cooldown allows.
 fn player_fire_system(mut commands: Commands,
-    kb: Res<Input<KeyCode>>,
+    kb: Res<TickInput>,
+    config: Res<GameConfig>,
     game_textures: Res<GameTextures>,
-    query: Query<&Transform, With<Player>>) 
+    mut query: Query<(&Transform, &ShipStats, &PowerUps, &mut Weapon), With<Player>>)
 {
-    if let Ok(player_tf) = query.get_single() {
-        if kb.just_pressed(KeyCode::Space) {
+    if let Ok((player_tf, ship_stats, power_ups, mut weapon)) = query.get_single_mut() {
+        let weapon_config = match config.weapons.get(&weapon.name) {
+            Some(weapon_config) => weapon_config,
+            None => return,
+        };
+        weapon.cooldown.tick(Duration::from_secs_f32(TIME_STEP));
+
+        let rapid_fire = power_ups.active(PickupKind::RapidFire);
+        let autofire = (weapon_config.autofire || rapid_fire) && kb.pressed(KeyCode::Space);
+        if !weapon.cooldown.finished() || !(kb.just_pressed(KeyCode::Space) || autofire) {
+            return;
+        }
+
+        let boost = if power_ups.active(PickupKind::SpreadShot) { 1 } else { 0 };
+        let level = weapon_config.level(weapon.level + boost);
+        let interval = if rapid_fire {
+            level.fire_interval.min(config.pickups.rapid_fire_interval)
+        } else {
+            level.fire_interval
+        };
+        weapon.cooldown = Timer::from_seconds(interval, false);
+
+        for shot in &level.shots {
             let (x, y) = (player_tf.translation.x, player_tf.translation.y);
-            let x_offset = PLAYER_SIZE.0 / 2. * SPRITE_SCALE - 5.;
-
-            let mut spawn_laser = |x_offset: f32| {
-                commands.spawn_bundle(SpriteBundle {
-                    texture: game_textures.player_laser.clone(),
-                    transform: Transform {
-                        translation: Vec3::new(x + x_offset, y + 15., 0.),
-                        scale: Vec3::new(SPRITE_SCALE, SPRITE_SCALE, 1.),
-                        ..Default::default()
-                    },
+            let translation = Vec3::new(x + shot.offset.0, y + shot.offset.1, 0.);
+            let angle = shot.angle.to_radians();
+            let speed = ship_stats.laser_speed * shot.speed;
+            commands.spawn_bundle(SpriteBundle {
+                texture: game_textures.player_laser.clone(),
+                transform: Transform {
+                    translation,
+                    rotation: Quat::from_rotation_z(-angle),
+                    scale: Vec3::new(config.sprite_scale, config.sprite_scale, 1.),
                     ..Default::default()
-                })
-                .insert(Laser)
-                .insert(FromPlayer)
-                .insert(SpriteSize::from(PLAYER_LASER_SIZE))
-                .insert(Movable{auto_despawn: true})
-                .insert(Velocity {x: 0., y: 1.});
-            };
-            spawn_laser(x_offset);
-            spawn_laser(-x_offset);
-
+                },
+                ..Default::default()
+            })
+            .insert(Laser)
+            .insert(FromPlayer)
+            .insert(Interpolated::from(translation))
+            .insert(SpriteSize::from(config.player.laser_size))
+            .insert(Collider::rotated_aabb(config.player.laser_size, angle, layers::PLAYER_LASER, layers::ENEMY))
+            .insert(Damage(config.player.laser_damage))
+            .insert(Movable{auto_despawn: true})
+            .insert(Velocity {x: angle.sin() * speed, y: angle.cos() * speed});
         }
-            
     }
 }
 
 fn player_keyboard_event_system(
-    kb: Res<Input<KeyCode>>,
+    kb: Res<TickInput>,
     mut query: Query<(&mut Velocity, &ShipStats), With<Player>>
 ) {
     if let Ok((mut velocity, ship_stats)) = query.get_single_mut() {
@@ -111,3 +158,20 @@ fn player_keyboard_event_system(
     }
 }
 
+
+/// Blinks an invulnerable player and makes them hittable again once the timer runs out.
+fn player_invulnerability_system(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Visibility), With<Player>>,
+) {
+    const BLINK: f32 = 0.1;
+    for (entity, mut invulnerable, mut visibility) in query.iter_mut() {
+        invulnerable.0.tick(Duration::from_secs_f32(TIME_STEP));
+        if invulnerable.0.finished() {
+            visibility.is_visible = true;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else {
+            visibility.is_visible = (invulnerable.0.elapsed_secs() / BLINK) as u32 % 2 == 1;
+        }
+    }
+}
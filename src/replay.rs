@@ -0,0 +1,188 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::input::{InputSource, TickInput};
+use crate::timestep::FixedUpdateStage;
+use crate::{playing_criteria, AppState, PlayerState};
+
+const MAGIC: &[u8; 4] = b"ORPL";
+const VERSION: u8 = 1;
+
+/// The seed and per-tick key state of one run, from entering `Playing` to game over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputRecording {
+    pub seed: u64,
+    pub ticks: Vec<u8>,
+}
+
+impl InputRecording {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, ticks: Vec::new() }
+    }
+
+    /// Serializes as a small header followed by run-length encoded `(mask, count)` pairs.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(17);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.ticks.len() as u32).to_le_bytes());
+
+        let mut ticks = self.ticks.iter().peekable();
+        while let Some(&mask) = ticks.next() {
+            let mut run: u8 = 1;
+            while run < u8::MAX && ticks.peek() == Some(&&mask) {
+                ticks.next();
+                run += 1;
+            }
+            bytes.push(mask);
+            bytes.push(run);
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+
+        if bytes.len() < 17 || &bytes[0..4] != MAGIC {
+            return Err(invalid("not an input recording"));
+        }
+        if bytes[4] != VERSION {
+            return Err(invalid("unsupported input recording version"));
+        }
+        let seed = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let len = u32::from_le_bytes(bytes[13..17].try_into().unwrap()) as usize;
+
+        let body = &bytes[17..];
+        if body.len() % 2 != 0 {
+            return Err(invalid("truncated input recording"));
+        }
+        let mut ticks = Vec::with_capacity(len);
+        for pair in body.chunks(2) {
+            ticks.extend(std::iter::repeat(pair[0]).take(pair[1] as usize));
+        }
+        if ticks.len() != len {
+            return Err(invalid("input recording length mismatch"));
+        }
+        Ok(Self { seed, ticks })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.encode())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::decode(&fs::read(path)?)
+    }
+}
+
+/// Captures input while playing; written to `path` on game over and on exit.
+pub struct Recorder {
+    pub recording: InputRecording,
+    pub path: Option<PathBuf>,
+}
+
+impl Recorder {
+    pub fn new(seed: u64, path: Option<PathBuf>) -> Self {
+        Self { recording: InputRecording::new(seed), path }
+    }
+
+    fn save(&self) {
+        if let Some(path) = &self.path {
+            match self.recording.save(path) {
+                Ok(()) => info!("saved input recording to {}", path.display()),
+                Err(err) => error!("failed to save input recording to {}: {err}", path.display()),
+            }
+        }
+    }
+}
+
+/// Feeds a recording into `TickInput` in place of the keyboard.
+pub struct Replay {
+    recording: InputRecording,
+    cursor: usize,
+}
+
+impl Replay {
+    pub fn new(recording: InputRecording) -> Self {
+        Self { recording, cursor: 0 }
+    }
+
+    pub fn finished(&self) -> bool {
+        self.cursor >= self.recording.ticks.len()
+    }
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set_to_stage(
+            FixedUpdateStage,
+            SystemSet::new()
+                .with_run_criteria(playing_criteria)
+                .with_system(replay_input_system.label(InputSource))
+                .with_system(record_input_system.after(InputSource)),
+        )
+        .add_system_set(SystemSet::on_enter(AppState::GameOver).with_system(save_recording_system))
+        .add_system_set(SystemSet::on_exit(AppState::GameOver).with_system(restart_recording_system))
+        .add_system_to_stage(CoreStage::Last, save_recording_on_exit_system);
+    }
+}
+
+fn replay_input_system(
+    replay: Option<ResMut<Replay>>,
+    player_state: Res<PlayerState>,
+    mut tick_input: ResMut<TickInput>,
+) {
+    let mut replay = match replay {
+        Some(replay) => replay,
+        None => return,
+    };
+
+    match replay.recording.ticks.get(replay.cursor) {
+        Some(&mask) => tick_input.set_mask(mask),
+        None => {
+            tick_input.set_mask(0);
+            return;
+        }
+    }
+
+    replay.cursor += 1;
+    if replay.finished() {
+        info!("replay finished with score {}", player_state.score);
+    }
+}
+
+fn record_input_system(recorder: Option<ResMut<Recorder>>, tick_input: Res<TickInput>) {
+    if let Some(mut recorder) = recorder {
+        recorder.recording.ticks.push(tick_input.mask());
+    }
+}
+
+fn save_recording_system(recorder: Option<Res<Recorder>>) {
+    if let Some(recorder) = recorder {
+        recorder.save();
+    }
+}
+
+fn restart_recording_system(recorder: Option<ResMut<Recorder>>) {
+    if let Some(mut recorder) = recorder {
+        recorder.recording.ticks.clear();
+    }
+}
+
+fn save_recording_on_exit_system(mut exit: EventReader<AppExit>, recorder: Option<Res<Recorder>>) {
+    if exit.iter().next().is_none() {
+        return;
+    }
+    if let Some(recorder) = recorder {
+        if !recorder.recording.ticks.is_empty() {
+            recorder.save();
+        }
+    }
+}
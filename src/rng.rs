@@ -0,0 +1,67 @@
+use rand::rngs::StdRng;
+use rand::{thread_rng, Error, Rng, RngCore, SeedableRng};
+
+const SEED_ARG: &str = "--seed";
+const SEED_ENV: &str = "ORION_SEED";
+
+/// Single source of randomness for gameplay. Every random decision must draw from this
+/// resource so that a seed (plus the player's input) reproduces a run exactly. Systems
+/// drawing from it need a fixed order against each other: in a tick, enemies spawn, then
+/// divers break out, then chargers move, then pickups drop.
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, rng: StdRng::seed_from_u64(seed) }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Restarts the sequence from the original seed.
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    /// Seed given by `--seed <n>` on the command line or the `ORION_SEED` environment
+    /// variable, in that order of precedence. A seed that isn't a number is an error rather
+    /// than a random run.
+    pub fn seed_from_env() -> Result<Option<u64>, String> {
+        let mut args = std::env::args().skip_while(|arg| arg != SEED_ARG).skip(1);
+        let seed = match args.next().or_else(|| std::env::var(SEED_ENV).ok()) {
+            Some(seed) => seed,
+            None => return Ok(None),
+        };
+        seed.parse()
+            .map(Some)
+            .map_err(|err| format!("invalid seed `{seed}`: {err}"))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(thread_rng().gen())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
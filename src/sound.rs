@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use crate::config::{GameConfig, SoundConfig};
+use crate::events::{EnemyDestroyed, LaserImpact, PlayerDied, PlayerHit};
+
+/// Plays sound effects for gameplay events. Needs the asset server and audio output.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameSounds>()
+            .add_system(sound_load_system)
+            .add_system(enemy_destroyed_sound_system)
+            .add_system(player_hit_sound_system)
+            .add_system(player_died_sound_system)
+            .add_system(laser_impact_sound_system);
+    }
+}
+
+/// Handles of the sounds named in `GameConfig::sounds`; `None` where no file is set.
+#[derive(Default)]
+pub struct GameSounds {
+    pub enemy_destroyed: Option<Handle<AudioSource>>,
+    pub player_hit: Option<Handle<AudioSource>>,
+    pub player_died: Option<Handle<AudioSource>>,
+    pub laser_impact: Option<Handle<AudioSource>>,
+}
+
+impl GameSounds {
+    fn load(asset_server: &AssetServer, config: &SoundConfig) -> Self {
+        let load = |path: &Option<String>| path.as_ref().map(|path| asset_server.load(path.as_str()));
+        GameSounds {
+            enemy_destroyed: load(&config.enemy_destroyed),
+            player_hit: load(&config.player_hit),
+            player_died: load(&config.player_died),
+            laser_impact: load(&config.laser_impact),
+        }
+    }
+}
+
+/// Loads the sounds at startup and again whenever the config changes.
+fn sound_load_system(
+    config: Res<GameConfig>,
+    asset_server: Res<AssetServer>,
+    mut sounds: ResMut<GameSounds>,
+) {
+    if config.is_changed() {
+        *sounds = GameSounds::load(&asset_server, &config.sounds);
+    }
+}
+
+/// Plays `sound` once if any events arrived, so a volley of hits doesn't stack up.
+fn play<E: 'static + Send + Sync>(audio: &Audio, sound: &Option<Handle<AudioSource>>, events: &mut EventReader<E>) {
+    if events.iter().count() > 0 {
+        if let Some(sound) = sound {
+            audio.play(sound.clone());
+        }
+    }
+}
+
+fn enemy_destroyed_sound_system(audio: Res<Audio>, sounds: Res<GameSounds>, mut events: EventReader<EnemyDestroyed>) {
+    play(&audio, &sounds.enemy_destroyed, &mut events);
+}
+
+fn player_hit_sound_system(audio: Res<Audio>, sounds: Res<GameSounds>, mut events: EventReader<PlayerHit>) {
+    play(&audio, &sounds.player_hit, &mut events);
+}
+
+fn player_died_sound_system(audio: Res<Audio>, sounds: Res<GameSounds>, mut events: EventReader<PlayerDied>) {
+    play(&audio, &sounds.player_died, &mut events);
+}
+
+fn laser_impact_sound_system(audio: Res<Audio>, sounds: Res<GameSounds>, mut events: EventReader<LaserImpact>) {
+    play(&audio, &sounds.laser_impact, &mut events);
+}
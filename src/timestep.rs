@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+
+use crate::components::Interpolated;
+use crate::TIME_STEP;
+
+/// Most ticks run in a single frame; any older backlog is dropped so a stall can't snowball.
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+/// Stage running the simulation at a fixed `TIME_STEP`, zero or more times per frame.
+#[derive(StageLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedUpdateStage;
+
+/// Accumulates frame time and pays it out in whole ticks of `step`.
+pub struct FixedTime {
+    step: Duration,
+    accumulator: Duration,
+    /// Ticks run every frame regardless of wall time, if set.
+    lockstep: Option<u32>,
+    ticking: bool,
+}
+
+impl FixedTime {
+    pub fn new(step: Duration) -> Self {
+        Self { step, accumulator: Duration::ZERO, lockstep: None, ticking: false }
+    }
+
+    /// Runs exactly one tick per frame regardless of wall time, for headless simulation.
+    pub fn lockstep(step: Duration) -> Self {
+        Self::lockstep_batched(step, 1)
+    }
+
+    /// Runs exactly `ticks` ticks per frame, like a client catching up after slow frames.
+    pub fn lockstep_batched(step: Duration, ticks: u32) -> Self {
+        Self { lockstep: Some(ticks.min(MAX_TICKS_PER_FRAME)), ..Self::new(step) }
+    }
+
+    pub fn step(&self) -> Duration {
+        self.step
+    }
+
+    /// Fraction of a tick accumulated since the last one ran, used to interpolate rendering.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.step.as_secs_f32()
+    }
+}
+
+impl Default for FixedTime {
+    fn default() -> Self {
+        Self::new(Duration::from_secs_f32(TIME_STEP))
+    }
+}
+
+pub struct TimestepPlugin;
+
+impl Plugin for TimestepPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FixedTime>().add_stage_after(
+            CoreStage::Update,
+            FixedUpdateStage,
+            SystemStage::parallel()
+                .with_run_criteria(fixed_update_criteria)
+                .with_system(restore_interpolated_system.exclusive_system().at_start())
+                .with_system(snapshot_interpolated_system.exclusive_system().at_end()),
+        );
+    }
+}
+
+fn fixed_update_criteria(time: Res<Time>, mut fixed: ResMut<FixedTime>) -> ShouldRun {
+    if !fixed.ticking {
+        let delta = match fixed.lockstep {
+            Some(ticks) => fixed.step * ticks,
+            None => time.delta(),
+        };
+        fixed.accumulator = (fixed.accumulator + delta).min(fixed.step * MAX_TICKS_PER_FRAME);
+    }
+
+    if fixed.accumulator >= fixed.step {
+        fixed.accumulator -= fixed.step;
+        fixed.ticking = true;
+        ShouldRun::YesAndCheckAgain
+    } else {
+        fixed.ticking = false;
+        ShouldRun::No
+    }
+}
+
+/// Puts simulated positions back before a tick, undoing any render interpolation.
+fn restore_interpolated_system(mut query: Query<(&mut Transform, &mut Interpolated)>) {
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        transform.translation = interpolated.current;
+        interpolated.previous = interpolated.current;
+    }
+}
+
+fn snapshot_interpolated_system(mut query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.current = transform.translation;
+    }
+}
+
+/// Blends interpolated entities between their last two ticks. Meant for the render side,
+/// after the fixed stage and before transform propagation.
+pub fn interpolate_transforms_system(
+    fixed: Res<FixedTime>,
+    mut query: Query<(&mut Transform, &Interpolated)>,
+) {
+    let alpha = fixed.alpha();
+    for (mut transform, interpolated) in query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, alpha);
+    }
+}
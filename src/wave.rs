@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::config::{GameConfig, WaveConfig};
+use crate::timestep::FixedUpdateStage;
+use crate::{playing_criteria, AppState, EnemyCount, TIME_STEP};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavePhase {
+    /// Waiting out the wave's delay; the "Wave N" banner is up.
+    Intermission,
+    /// Enemies are still being spawned.
+    Spawning,
+    /// Everything is spawned; the wave ends when the last enemy is gone.
+    Fighting,
+    /// The wave's enemies are cleared and its boss is coming in; the wave ends when it's
+    /// destroyed.
+    Boss,
+}
+
+/// Progress through the current wave. Its `config` is resolved when the wave starts, so
+/// tuning changes apply from the next wave on.
+pub struct CurrentWave {
+    pub number: u32,
+    pub config: WaveConfig,
+    pub phase: WavePhase,
+    pub spawned: u32,
+    boss_spawned: bool,
+    timer: Timer,
+}
+
+impl CurrentWave {
+    pub fn new(number: u32, config: &GameConfig) -> Self {
+        let wave = config.wave(number);
+        let timer = Timer::from_seconds(wave.delay, false);
+        Self { number, config: wave, phase: WavePhase::Intermission, spawned: 0, boss_spawned: false, timer }
+    }
+
+    /// Ticks the spawn timer and reports whether the next enemy is due.
+    pub fn spawn_due(&mut self) -> bool {
+        self.phase == WavePhase::Spawning
+            && self.timer.tick(Duration::from_secs_f32(TIME_STEP)).just_finished()
+    }
+
+    /// Records a spawned enemy, moving on to `Fighting` once the wave is complete.
+    pub fn spawned_one(&mut self) {
+        self.spawned += 1;
+        if self.spawned >= self.config.enemy_count {
+            self.phase = WavePhase::Fighting;
+        }
+    }
+
+    /// Reports, once per wave, that its boss is due.
+    pub fn boss_due(&mut self) -> bool {
+        let due = self.phase == WavePhase::Boss && !self.boss_spawned;
+        self.boss_spawned |= due;
+        due
+    }
+}
+
+impl FromWorld for CurrentWave {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(1, world.resource::<GameConfig>())
+    }
+}
+
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentWave>()
+            .add_system_set(SystemSet::on_enter(AppState::Playing).with_system(wave_start_system))
+            .add_system_set_to_stage(
+                FixedUpdateStage,
+                SystemSet::new().with_run_criteria(playing_criteria).with_system(wave_progress_system),
+            );
+    }
+}
+
+fn wave_start_system(mut wave: ResMut<CurrentWave>, config: Res<GameConfig>) {
+    *wave = CurrentWave::new(1, &config);
+}
+
+fn wave_progress_system(
+    mut wave: ResMut<CurrentWave>,
+    config: Res<GameConfig>,
+    enemy_count: Res<EnemyCount>,
+) {
+    match wave.phase {
+        WavePhase::Intermission => {
+            if wave.timer.tick(Duration::from_secs_f32(TIME_STEP)).finished() {
+                // First enemy right away, the rest every `spawn_interval`.
+                let interval = Duration::from_secs_f32(wave.config.spawn_interval);
+                wave.timer = Timer::new(interval, true);
+                wave.timer.set_elapsed(interval.saturating_sub(Duration::from_secs_f32(TIME_STEP)));
+                wave.phase = WavePhase::Spawning;
+            }
+        }
+        WavePhase::Spawning => {}
+        WavePhase::Fighting => {
+            if enemy_count.0 == 0 {
+                if wave.config.boss.is_some() {
+                    wave.phase = WavePhase::Boss;
+                } else {
+                    *wave = CurrentWave::new(wave.number + 1, &config);
+                }
+            }
+        }
+        WavePhase::Boss => {
+            if wave.boss_spawned && enemy_count.0 == 0 {
+                *wave = CurrentWave::new(wave.number + 1, &config);
+            }
+        }
+    }
+}
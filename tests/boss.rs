@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use invaders::components::{layers, Boss, BossPart, Collider, Damage, Enemy, Explosion, FromPlayer, Health, Laser};
+use invaders::config::GameConfig;
+use invaders::wave::{CurrentWave, WavePhase};
+use invaders::EnemyCount;
+
+mod common;
+
+/// A first wave of a single scout, closed by the mothership.
+fn boss_app() -> App {
+    common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        let wave = &mut config.waves[0];
+        wave.delay = 0.;
+        wave.fire_rate = 0.;
+        wave.enemy_count = 1;
+        wave.enemies = vec![("scout".to_string(), 1)];
+        wave.formation.members = 1;
+        wave.boss = Some("mothership".to_string());
+        app.insert_resource(config);
+    })
+}
+
+fn shoot(app: &mut App, at: Vec3, damage: u32) {
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(at)))
+        .insert(Laser)
+        .insert(FromPlayer)
+        .insert(Damage(damage))
+        .insert(Collider::aabb((9., 54.), layers::PLAYER_LASER, layers::ENEMY));
+}
+
+fn boss(app: &mut App) -> Option<Entity> {
+    app.world.query_filtered::<Entity, With<Boss>>().iter(&app.world).next()
+}
+
+/// Shoots down the wave's scout and waits for the boss to come in.
+fn clear_wave(app: &mut App) -> Entity {
+    common::step(app, 5);
+    let scout = app.world.query_filtered::<&Transform, With<Enemy>>().single(&app.world).translation;
+    shoot(app, scout, 1);
+    common::step(app, 3);
+    boss(app).expect("boss should come in once the wave is cleared")
+}
+
+fn shoot_boss(app: &mut App, damage: u32) {
+    let part = app.world.query_filtered::<&Transform, With<BossPart>>().iter(&app.world).next().unwrap().translation;
+    shoot(app, part, damage);
+    common::step(app, 2);
+}
+
+#[test]
+fn boss_closes_its_wave() {
+    let mut app = boss_app();
+    common::step(&mut app, 5);
+    assert!(boss(&mut app).is_none());
+
+    clear_wave(&mut app);
+    let wave = app.world.resource::<CurrentWave>();
+    assert_eq!((wave.number, wave.phase), (1, WavePhase::Boss));
+    assert_eq!(app.world.query::<&BossPart>().iter(&app.world).count(), 3);
+}
+
+#[test]
+fn boss_changes_phase_as_it_is_worn_down_and_goes_up_in_a_chain() {
+    let mut app = boss_app();
+    let boss_entity = clear_wave(&mut app);
+    let hit_points = app.world.get::<Health>(boss_entity).unwrap().max;
+
+    shoot_boss(&mut app, hit_points / 2);
+    assert_eq!(app.world.get::<Boss>(boss_entity).unwrap().phase, 1);
+    shoot_boss(&mut app, hit_points / 4);
+    assert_eq!(app.world.get::<Boss>(boss_entity).unwrap().phase, 2);
+
+    shoot_boss(&mut app, hit_points);
+    assert!(boss(&mut app).is_none());
+    assert_eq!(app.world.query::<&BossPart>().iter(&app.world).count(), 0);
+    assert_eq!(app.world.resource::<CurrentWave>().number, 2);
+
+    common::step(&mut app, 30);
+    let explosions: Vec<f32> = app
+        .world
+        .query_filtered::<&Transform, With<Explosion>>()
+        .iter(&app.world)
+        .map(|tf| tf.scale.x)
+        .collect();
+    assert!(explosions.len() > 1, "the wreck should keep exploding");
+    let scale = GameConfig::default().bosses["mothership"].explosion_scale;
+    assert!(explosions.contains(&scale), "the wreck should go up in large explosions");
+}
+
+#[test]
+fn one_laser_never_hits_both_an_enemy_and_the_boss() {
+    let mut app = boss_app();
+    let boss_entity = clear_wave(&mut app);
+    let hit_points = app.world.get::<Health>(boss_entity).unwrap().max;
+    let part = app.world.query_filtered::<&Transform, With<BossPart>>().iter(&app.world).next().unwrap().translation;
+
+    let config = GameConfig::default();
+    let kind = &config.enemies["scout"];
+    app.world.resource_mut::<EnemyCount>().0 += 1;
+    let enemy = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(part)))
+        .insert(Enemy)
+        .insert(kind.kind("scout"))
+        .insert(Health::new(kind.hit_points))
+        .insert(Collider::aabb(kind.size, layers::ENEMY, layers::PLAYER_LASER))
+        .id();
+    shoot(&mut app, part, 1);
+    common::step(&mut app, 1);
+
+    let enemy_hit = app.world.get::<Health>(enemy).map_or(true, |health| health.current < health.max);
+    let boss_hit = app.world.get::<Health>(boss_entity).unwrap().current < hit_points;
+    assert!(enemy_hit != boss_hit, "the laser should hit exactly one of them");
+}
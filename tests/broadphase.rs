@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+use invaders::broadphase::SpatialGrid;
+use invaders::collision::WorldShape;
+use invaders::components::{layers, Collider};
+
+#[test]
+fn query_finds_boxes_spanning_cells_once() {
+    let mut grid = SpatialGrid::new(10.);
+    let wide = Entity::from_raw(1);
+    let far = Entity::from_raw(2);
+    grid.insert(wide, Vec2::new(0., 0.), Vec2::new(30., 4.));
+    grid.insert(far, Vec2::new(-100., -100.), Vec2::new(4., 4.));
+
+    assert_eq!(grid.query(Vec2::new(12., 0.), Vec2::new(2., 2.)), vec![wide]);
+    assert_eq!(grid.query(Vec2::new(-12., 0.), Vec2::new(30., 2.)), vec![wide]);
+    assert_eq!(grid.query(Vec2::new(-101., -99.), Vec2::new(2., 2.)), vec![far]);
+    assert!(grid.query(Vec2::new(50., 50.), Vec2::new(2., 2.)).is_empty());
+}
+
+#[test]
+fn clear_empties_the_grid() {
+    let mut grid = SpatialGrid::new(10.);
+    grid.insert(Entity::from_raw(1), Vec2::ZERO, Vec2::ONE);
+    grid.clear();
+
+    assert!(grid.query(Vec2::ZERO, Vec2::ONE).is_empty());
+}
+
+#[test]
+fn shapes_overlap_by_their_own_geometry() {
+    let at = |x: f32, y: f32| Transform::from_xyz(x, y, 0.);
+    let shape = |collider: &Collider, x: f32, y: f32| WorldShape::new(collider, &at(x, y));
+    let square = Collider::aabb((10., 10.), layers::ENEMY, layers::PLAYER_LASER);
+    let inset = square.with_inset(2.);
+    let circle = Collider::circle(5., layers::PLAYER_LASER, layers::ENEMY);
+
+    assert!(shape(&square, 0., 0.).overlaps(&shape(&circle, 9., 0.)));
+    // Near the corner the circle misses where a box of the same size would touch.
+    assert!(!shape(&square, 0., 0.).overlaps(&shape(&circle, 9., 9.)));
+    assert!(shape(&square, 0., 0.).overlaps(&shape(&square, 9., 0.)));
+    assert!(!shape(&inset, 0., 0.).overlaps(&shape(&square, 9., 0.)));
+}
+
+#[test]
+fn layers_interact_when_either_mask_matches() {
+    let laser = Collider::aabb((1., 1.), layers::PLAYER_LASER, 0);
+    let enemy = Collider::aabb((1., 1.), layers::ENEMY, layers::PLAYER_LASER);
+    let enemy_laser = Collider::aabb((1., 1.), layers::ENEMY_LASER, layers::PLAYER);
+
+    assert!(laser.interacts_with(&enemy));
+    assert!(enemy.interacts_with(&laser));
+    assert!(!laser.interacts_with(&enemy_laser));
+}
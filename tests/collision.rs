@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use invaders::components::{layers, Collider, ColliderShape, Damage, Enemy, FromPlayer, Health, HitFlash, Laser, Movable, SpriteSize, Velocity};
+use invaders::config::GameConfig;
+use invaders::events::{EnemyDestroyed, LaserImpact};
+use invaders::{EnemyCount, PlayerState};
+
+mod common;
+
+fn spawn_enemy(app: &mut App, name: &str, x: f32, y: f32) -> Entity {
+    let config = GameConfig::default();
+    let kind = &config.enemies[name];
+    app.world.resource_mut::<EnemyCount>().0 += 1;
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(x, y, 10.)))
+        .insert(Enemy)
+        .insert(kind.kind(name))
+        .insert(Health::new(kind.hit_points))
+        .insert(SpriteSize::from(kind.size))
+        .insert(Collider::aabb(kind.size, layers::ENEMY, layers::PLAYER_LASER))
+        .id()
+}
+
+fn spawn_player_laser(app: &mut App, x: f32, y: f32) -> Entity {
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(x, y, 0.)))
+        .insert(Laser)
+        .insert(FromPlayer)
+        .insert(SpriteSize::from(GameConfig::default().player.laser_size))
+        .insert(Collider::aabb(GameConfig::default().player.laser_size, layers::PLAYER_LASER, layers::ENEMY))
+        .insert(Damage(1))
+        .insert(Movable { auto_despawn: true })
+        .insert(Velocity { x: 0., y: 1. })
+        .id()
+}
+
+#[test]
+fn player_laser_overlapping_enemy_despawns_both_and_scores() {
+    let mut app = common::headless_app();
+    let enemy = spawn_enemy(&mut app, "scout", 0., 100.);
+    let laser = spawn_player_laser(&mut app, 0., 100.);
+
+    common::step(&mut app, 1);
+
+    assert!(app.world.get_entity(enemy).is_none());
+    assert!(app.world.get_entity(laser).is_none());
+    let scout_score = GameConfig::default().enemies["scout"].score;
+    assert_eq!(app.world.resource::<PlayerState>().score, scout_score);
+}
+
+#[test]
+fn player_laser_missing_enemy_leaves_both() {
+    let mut app = common::headless_app();
+    let enemy = spawn_enemy(&mut app, "scout", -200., 100.);
+    let laser = spawn_player_laser(&mut app, 200., 100.);
+
+    common::step(&mut app, 1);
+
+    assert!(app.world.get_entity(enemy).is_some());
+    assert!(app.world.get_entity(laser).is_some());
+    assert_eq!(app.world.resource::<PlayerState>().score, 0);
+}
+
+#[test]
+fn tough_enemy_survives_until_its_hit_points_run_out() {
+    let mut app = common::headless_app();
+    let hit_points = GameConfig::default().enemies["tank"].hit_points;
+    let enemy = spawn_enemy(&mut app, "tank", 0., 100.);
+
+    for hit in 1..hit_points {
+        let laser = spawn_player_laser(&mut app, 0., 100.);
+        common::step(&mut app, 1);
+
+        assert!(app.world.get_entity(laser).is_none());
+        assert_eq!(app.world.get::<Health>(enemy).unwrap().current, hit_points - hit);
+        assert!(app.world.get::<HitFlash>(enemy).is_some());
+        assert_eq!(app.world.resource::<PlayerState>().score, 0);
+    }
+
+    spawn_player_laser(&mut app, 0., 100.);
+    common::step(&mut app, 1);
+
+    assert!(app.world.get_entity(enemy).is_none());
+    let tank_score = GameConfig::default().enemies["tank"].score;
+    assert_eq!(app.world.resource::<PlayerState>().score, tank_score);
+}
+
+#[test]
+fn destroying_an_enemy_sends_events() {
+    let mut app = common::headless_app();
+    let enemy = spawn_enemy(&mut app, "scout", 0., 100.);
+    spawn_player_laser(&mut app, 0., 100.);
+
+    common::step(&mut app, 1);
+
+    let destroyed: Vec<_> = app
+        .world
+        .resource::<Events<EnemyDestroyed>>()
+        .iter_current_update_events()
+        .cloned()
+        .collect();
+    assert_eq!(destroyed.len(), 1);
+    assert_eq!(destroyed[0].enemy, enemy);
+    assert_eq!(destroyed[0].kind, "scout");
+    assert_eq!(destroyed[0].position, Vec3::new(0., 100., 10.));
+
+    let impacts = app.world.resource::<Events<LaserImpact>>();
+    assert_eq!(impacts.iter_current_update_events().filter(|impact| impact.from_player).count(), 1);
+}
+
+#[test]
+fn turned_laser_hitbox_covers_the_turned_sprite() {
+    let upright = Collider::rotated_aabb((10., 40.), 0., layers::PLAYER_LASER, layers::ENEMY);
+    assert_eq!(upright.shape, ColliderShape::Aabb(Vec2::new(10., 40.)));
+
+    let sideways = Collider::rotated_aabb((10., 40.), std::f32::consts::FRAC_PI_2, layers::PLAYER_LASER, layers::ENEMY);
+    match sideways.shape {
+        ColliderShape::Aabb(size) => assert!((size - Vec2::new(40., 10.)).length() < 1e-3),
+        ColliderShape::Circle(_) => panic!("expected a box"),
+    }
+}
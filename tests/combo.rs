@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use invaders::combo::Combo;
+use invaders::config::GameConfig;
+use invaders::events::EnemyDestroyed;
+use invaders::{EnemyCount, PlayerState};
+
+mod common;
+
+fn destroy_enemy(app: &mut App, score: i64) {
+    app.world.resource_mut::<EnemyCount>().0 += 1;
+    app.world.resource_mut::<Events<EnemyDestroyed>>().send(EnemyDestroyed {
+        enemy: Entity::from_raw(0),
+        kind: "scout".to_string(),
+        score,
+        position: Vec3::ZERO,
+    });
+}
+
+#[test]
+fn chained_kills_raise_the_multiplier() {
+    let mut app = common::headless_app();
+    for _ in 0..3 {
+        destroy_enemy(&mut app, 10);
+        common::step(&mut app, 1);
+    }
+
+    assert_eq!(app.world.resource::<Combo>().multiplier, 3);
+    assert_eq!(app.world.resource::<PlayerState>().score, 10 + 20 + 30);
+}
+
+#[test]
+fn multiplier_decays_one_step_per_window_and_caps() {
+    let config = GameConfig::default().combo;
+    let window = Duration::from_secs_f32(config.window);
+    let mut combo = Combo::default();
+
+    for _ in 0..config.max_multiplier + 2 {
+        combo.kill(&config);
+    }
+    assert_eq!(combo.multiplier, config.max_multiplier);
+
+    combo.tick(window);
+    assert_eq!(combo.multiplier, config.max_multiplier - 1);
+    combo.tick(window * 2);
+    assert_eq!(combo.multiplier, config.max_multiplier - 3);
+
+    combo.reset();
+    assert_eq!(combo.kill(&config), 1);
+}
@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use invaders::timestep::FixedTime;
+use invaders::{AppState, GameplayPlugin, WinSize, TIME_STEP};
+
+/// Builds a windowless app running only the gameplay simulation, already in `Playing`.
+/// Every `app.update()` advances exactly one fixed tick.
+pub fn headless_app() -> App {
+    headless_app_with(|_| {})
+}
+
+/// Like `headless_app`, with `setup` run before `GameplayPlugin` is added so it can inject
+/// resources such as a fixed `GameRng`.
+pub fn headless_app_with(setup: impl FnOnce(&mut App)) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(WinSize { w: 676., h: 676. })
+        .insert_resource(FixedTime::lockstep(Duration::from_secs_f32(TIME_STEP)));
+    setup(&mut app);
+    app.add_plugin(GameplayPlugin);
+
+    app.update();
+    app.world
+        .resource_mut::<State<AppState>>()
+        .set(AppState::Playing)
+        .unwrap();
+    app.update();
+    app
+}
+
+/// Makes every update of an app being set up run `ticks` ticks, as slow frames do.
+pub fn batch_ticks(app: &mut App, ticks: u32) {
+    app.insert_resource(FixedTime::lockstep_batched(Duration::from_secs_f32(TIME_STEP), ticks));
+}
+
+/// Advances the simulation by `frames` updates, one tick each unless ticks are batched.
+pub fn step(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
@@ -0,0 +1,61 @@
+use invaders::config::{ConfigError, GameConfig, GridConfig};
+
+#[test]
+fn shipped_config_is_valid() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/config.ron");
+    let config = GameConfig::load(path).unwrap();
+    assert_eq!(config.explosion.len(), 16);
+}
+
+#[test]
+fn invalid_values_are_all_reported() {
+    let mut config = GameConfig::default();
+    config.base_speed = 0.;
+    config.player.stats.decel_speed = 0.;
+
+    match config.validate() {
+        Err(ConfigError::Invalid(problems)) => {
+            assert_eq!(problems.len(), 2);
+            assert!(problems[0].contains("base_speed"));
+            assert!(problems[1].contains("decel_speed"));
+        }
+        other => panic!("expected validation errors, got {other:?}"),
+    }
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/config.ron"))
+        .unwrap()
+        .replacen("base_speed", "base_sped", 1);
+    assert!(matches!(GameConfig::parse(&text), Err(ConfigError::Parse(_))));
+}
+
+#[test]
+fn waves_must_refer_to_known_paths() {
+    let mut config = GameConfig::default();
+    config.waves[0].formation.paths = vec!["zigzag".to_string()];
+
+    match config.validate() {
+        Err(ConfigError::Invalid(problems)) => assert!(problems[0].contains("unknown path `zigzag`")),
+        other => panic!("expected validation errors, got {other:?}"),
+    }
+}
+
+#[test]
+fn grid_waves_escalate_by_whole_rows() {
+    let mut config = GameConfig::default();
+    config.escalation.enemy_count = 4;
+    let last = config.waves.len() as u32;
+    config.waves.last_mut().unwrap().grid = Some(GridConfig {
+        rows: 2,
+        columns: 3,
+        spacing: (80., 50.),
+        step_down: 25.,
+        speed_up: 5.,
+    });
+
+    let wave = config.wave(last + 2);
+    assert_eq!(wave.grid.as_ref().unwrap().rows, 5);
+    assert_eq!(wave.enemy_count, 15);
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use invaders::components::{Damage, Diving, Enemy, Player};
+use invaders::config::GameConfig;
+use invaders::PlayerState;
+
+mod common;
+
+/// One wave of a single formation of three scouts sending a diver every second.
+fn diving_app() -> App {
+    common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        let wave = &mut config.waves[0];
+        wave.delay = 0.;
+        wave.spawn_interval = 0.1;
+        wave.fire_rate = 0.;
+        wave.enemy_count = 3;
+        wave.enemies = vec![("scout".to_string(), 1)];
+        wave.formation.members = 3;
+        wave.formation.dive_interval = 1.;
+        config.waves.truncate(1);
+        app.insert_resource(config);
+    })
+}
+
+fn divers(app: &mut App) -> Vec<Entity> {
+    app.world
+        .query_filtered::<Entity, (With<Enemy>, With<Diving>)>()
+        .iter(&app.world)
+        .collect()
+}
+
+#[test]
+fn formation_members_break_out_to_dive() {
+    let mut app = diving_app();
+    common::step(&mut app, 50);
+    assert!(divers(&mut app).is_empty());
+
+    common::step(&mut app, 20);
+    assert_eq!(divers(&mut app).len(), 1);
+}
+
+#[test]
+fn ramming_hurts_the_player_once_per_dive() {
+    let mut app = diving_app();
+    common::step(&mut app, 70);
+    let diver = divers(&mut app)[0];
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    let health = app.world.resource::<PlayerState>().health;
+
+    let translation = app.world.get::<Transform>(player).unwrap().translation;
+    app.world.get_mut::<Transform>(diver).unwrap().translation = translation;
+    common::step(&mut app, 1);
+
+    let damage = GameConfig::default().dive.damage as i64;
+    assert_eq!(app.world.resource::<PlayerState>().health, health - damage);
+    assert!(app.world.get::<Damage>(diver).is_none(), "the diver should only ram once");
+
+    app.world.get_mut::<Transform>(diver).unwrap().translation = translation;
+    common::step(&mut app, 1);
+    assert_eq!(app.world.resource::<PlayerState>().health, health - damage);
+}
+
+#[test]
+fn chargers_ram_the_player_once_per_pass() {
+    let mut app = common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        let wave = &mut config.waves[0];
+        wave.delay = 0.;
+        wave.fire_rate = 0.;
+        wave.enemy_count = 1;
+        wave.enemies = vec![("kamikaze".to_string(), 1)];
+        config.waves.truncate(1);
+        app.insert_resource(config);
+    });
+    common::step(&mut app, 5);
+    let charger = app.world.query_filtered::<Entity, With<Enemy>>().single(&app.world);
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    let health = app.world.resource::<PlayerState>().health;
+
+    let translation = app.world.get::<Transform>(player).unwrap().translation;
+    app.world.get_mut::<Transform>(charger).unwrap().translation = translation;
+    common::step(&mut app, 1);
+
+    let damage = GameConfig::default().dive.damage as i64;
+    assert_eq!(app.world.resource::<PlayerState>().health, health - damage);
+    assert!(app.world.get::<Damage>(charger).is_none(), "the charger should only ram once per pass");
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use invaders::components::{Collider, ColliderShape, Enemy, EnemyGun, FirePattern, FromEnemy, Laser, Player, Velocity};
+use invaders::config::GameConfig;
+
+mod common;
+
+/// Spawns a scout firing `pattern`, due to fire on the next tick.
+fn spawn_gunner(app: &mut App, pattern: FirePattern, x: f32, y: f32) -> Entity {
+    let mut kind = GameConfig::default().enemies["scout"].kind("scout");
+    kind.fire = pattern;
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(x, y, 10.)))
+        .insert(Enemy)
+        .insert(kind)
+        .insert(EnemyGun { timer: Timer::from_seconds(0., false), burst_left: 1, spiral: 0. })
+        .id()
+}
+
+fn enemy_lasers(app: &mut App) -> Vec<Vec2> {
+    app.world
+        .query_filtered::<&Velocity, (With<Laser>, With<FromEnemy>)>()
+        .iter(&app.world)
+        .map(|velocity| Vec2::new(velocity.x, velocity.y))
+        .collect()
+}
+
+fn player_position(app: &mut App) -> Vec2 {
+    let mut query = app.world.query_filtered::<&Transform, With<Player>>();
+    query.single(&app.world).translation.truncate()
+}
+
+#[test]
+fn aimed_shots_head_for_the_player() {
+    let mut app = common::headless_app();
+    spawn_gunner(&mut app, FirePattern::Aimed, 200., 200.);
+    common::step(&mut app, 1);
+
+    let lasers = enemy_lasers(&mut app);
+    assert_eq!(lasers.len(), 1);
+    let towards_player = (player_position(&mut app) - Vec2::new(200., 185.)).normalize();
+    assert!(lasers[0].angle_between(towards_player).abs() < 0.05);
+}
+
+#[test]
+fn spreads_fan_out_around_straight_down() {
+    let mut app = common::headless_app();
+    spawn_gunner(&mut app, FirePattern::Spread { count: 5, arc: 60., aimed: false }, 0., 200.);
+    common::step(&mut app, 1);
+
+    let mut xs: Vec<f32> = enemy_lasers(&mut app).iter().map(|velocity| velocity.x).collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(xs.len(), 5);
+    assert!((xs[0] + 0.5).abs() < 1e-4 && xs[2].abs() < 1e-4 && (xs[4] - 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn spirals_turn_between_volleys() {
+    let mut app = common::headless_app();
+    let enemy = spawn_gunner(&mut app, FirePattern::Spiral { count: 4, step: 10. }, 0., 200.);
+    common::step(&mut app, 1);
+    assert_eq!(enemy_lasers(&mut app).len(), 4);
+
+    let spiral = app.world.get::<EnemyGun>(enemy).unwrap().spiral;
+    assert!((spiral - 10f32.to_radians()).abs() < 1e-4);
+}
+
+#[test]
+fn sideways_shots_get_sideways_hitboxes() {
+    let mut app = common::headless_app();
+    spawn_gunner(&mut app, FirePattern::Spiral { count: 4, step: 0. }, 0., 200.);
+    common::step(&mut app, 1);
+
+    let mut query = app.world.query_filtered::<(&Velocity, &Collider), (With<Laser>, With<FromEnemy>)>();
+    for (velocity, collider) in query.iter(&app.world) {
+        let size = match collider.shape {
+            ColliderShape::Aabb(size) => size,
+            ColliderShape::Circle(_) => panic!("expected a box"),
+        };
+        assert_eq!(velocity.x.abs() > velocity.y.abs(), size.x > size.y);
+    }
+}
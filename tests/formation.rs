@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+use invaders::components::{layers, Collider, Damage, Enemy, FromPlayer, Laser};
+use invaders::config::GameConfig;
+use invaders::PlayerState;
+
+mod common;
+
+/// One wave of a single formation of `members` scouts, spawned in quick succession.
+fn formation_app(members: u32) -> App {
+    common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        let wave = &mut config.waves[0];
+        wave.delay = 0.;
+        wave.spawn_interval = 0.1;
+        wave.fire_rate = 0.;
+        wave.enemy_count = members;
+        wave.enemies = vec![("scout".to_string(), 1)];
+        wave.formation.members = members;
+        config.waves.truncate(1);
+        app.insert_resource(config);
+    })
+}
+
+fn enemies(app: &mut App) -> Vec<Vec3> {
+    app.world
+        .query_filtered::<&Transform, With<Enemy>>()
+        .iter(&app.world)
+        .map(|tf| tf.translation)
+        .collect()
+}
+
+fn shoot(app: &mut App, at: Vec3) {
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(at)))
+        .insert(Laser)
+        .insert(FromPlayer)
+        .insert(Damage(1))
+        .insert(Collider::aabb((9., 54.), layers::PLAYER_LASER, layers::ENEMY));
+}
+
+#[test]
+fn members_trail_each_other_along_the_path() {
+    let mut app = formation_app(3);
+    common::step(&mut app, 120);
+
+    let positions = enemies(&mut app);
+    assert_eq!(positions.len(), 3);
+    for (i, a) in positions.iter().enumerate() {
+        for b in &positions[i + 1..] {
+            assert!(a.distance(*b) > 30., "members should keep their spacing");
+        }
+    }
+}
+
+#[test]
+fn wiping_out_a_formation_awards_its_bonus() {
+    let mut app = formation_app(2);
+    common::step(&mut app, 120);
+
+    let positions = enemies(&mut app);
+    assert_eq!(positions.len(), 2);
+    for position in positions {
+        shoot(&mut app, position);
+    }
+    common::step(&mut app, 1);
+
+    assert!(enemies(&mut app).is_empty());
+    let config = GameConfig::default();
+    let scout = config.enemies["scout"].score;
+    // Two kills in a row score at ×1 and ×2.
+    assert_eq!(app.world.resource::<PlayerState>().score, scout * 3 + config.waves[0].formation.bonus);
+}
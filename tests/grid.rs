@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use invaders::components::{Enemy, GridFormation, GridMember};
+use invaders::config::{GameConfig, GridConfig};
+use invaders::EnemyCount;
+
+mod common;
+
+/// A single grid wave starting right away.
+fn grid_app(rows: u32, columns: u32) -> App {
+    common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        let mut wave = config.waves[3].clone();
+        wave.delay = 0.;
+        wave.fire_rate = 0.;
+        wave.grid = Some(GridConfig { rows, columns, spacing: (80., 50.), step_down: 25., speed_up: 5. });
+        config.waves = vec![wave];
+        app.insert_resource(config);
+    })
+}
+
+fn grid(app: &mut App) -> GridFormation {
+    app.world.query::<&GridFormation>().single(&app.world).clone()
+}
+
+#[test]
+fn grid_waves_spawn_every_member_at_once() {
+    let mut app = grid_app(3, 4);
+    common::step(&mut app, 1);
+
+    let members = app.world.query_filtered::<&GridMember, With<Enemy>>().iter(&app.world).count();
+    assert_eq!(members, 12);
+    assert_eq!(app.world.resource::<EnemyCount>().0, 12);
+}
+
+#[test]
+fn grids_step_down_and_turn_at_the_edge() {
+    let mut app = grid_app(2, 3);
+    common::step(&mut app, 2);
+    let start = grid(&mut app);
+    assert_eq!(start.direction, 1.);
+
+    let mut turned = None;
+    for _ in 0..2000 {
+        common::step(&mut app, 1);
+        if grid(&mut app).direction < 0. {
+            turned = Some(grid(&mut app));
+            break;
+        }
+    }
+    let turned = turned.expect("grid should reach the right edge");
+    assert!(turned.step_left > 0.);
+
+    common::step(&mut app, 120);
+    assert!(grid(&mut app).origin.y <= start.origin.y - start.step_down + 1e-3);
+}
+
+#[test]
+fn grids_speed_up_as_members_die() {
+    let grid = GridFormation {
+        origin: Vec2::ZERO,
+        direction: 1.,
+        speed: 10.,
+        speed_up: 5.,
+        step_down: 25.,
+        step_left: 0.,
+        members: 9,
+    };
+    assert_eq!(grid.current_speed(9), 10.);
+    assert!(grid.current_speed(5) > 10. && grid.current_speed(5) < 50.);
+    assert_eq!(grid.current_speed(1), 50.);
+}
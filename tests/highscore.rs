@@ -0,0 +1,42 @@
+use invaders::highscore::{HighScore, HighScores, MAX_ENTRIES};
+
+fn entry(initials: &str, score: i64) -> HighScore {
+    HighScore {
+        initials: initials.to_string(),
+        score,
+        wave: 1,
+        date: "2024-01-01".to_string(),
+    }
+}
+
+#[test]
+fn table_keeps_the_best_scores_in_order() {
+    let mut scores = HighScores::default();
+    for score in 1..=MAX_ENTRIES as i64 {
+        assert!(scores.qualifies(score * 100));
+        scores.insert(entry("AAA", score * 100));
+    }
+
+    assert!(!scores.qualifies(100));
+    assert_eq!(scores.insert(entry("BBB", 550)), Some(5));
+    assert_eq!(scores.insert(entry("CCC", 50)), None);
+
+    assert_eq!(scores.entries.len(), MAX_ENTRIES);
+    assert_eq!(scores.best(), Some(1000));
+    assert!(scores.entries.windows(2).all(|pair| pair[0].score >= pair[1].score));
+    assert_eq!(scores.entries.last().unwrap().score, 200);
+}
+
+#[test]
+fn table_round_trips_through_its_file() {
+    let path = std::env::temp_dir().join(format!("orion-highscores-{}.ron", std::process::id()));
+    let mut scores = HighScores::load(&path);
+    assert!(scores.entries.is_empty());
+
+    scores.insert(entry("ABC", 1234));
+    scores.save().unwrap();
+    let loaded = HighScores::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.entries, scores.entries);
+}
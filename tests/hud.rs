@@ -0,0 +1,85 @@
+use bevy::asset::AssetPlugin;
+use bevy::prelude::*;
+use invaders::components::{
+    layers, Boss, BossBar, BossBarFill, BossPart, Collider, Damage, Enemy, FromPlayer, Health, HealthPips, Laser,
+    LifeIcons,
+};
+use invaders::config::GameConfig;
+use invaders::hud::HudPlugin;
+use invaders::PlayerState;
+
+mod common;
+
+/// A first wave of a single scout, closed by the mothership, with the HUD drawn over it.
+fn hud_app() -> App {
+    common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        let wave = &mut config.waves[0];
+        wave.delay = 0.;
+        wave.fire_rate = 0.;
+        wave.enemy_count = 1;
+        wave.enemies = vec![("scout".to_string(), 1)];
+        wave.formation.members = 1;
+        wave.boss = Some("mothership".to_string());
+        app.insert_resource(config).add_plugin(AssetPlugin).add_plugin(HudPlugin);
+    })
+}
+
+fn shoot(app: &mut App, at: Vec3, damage: u32) {
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(at)))
+        .insert(Laser)
+        .insert(FromPlayer)
+        .insert(Damage(damage))
+        .insert(Collider::aabb((9., 54.), layers::PLAYER_LASER, layers::ENEMY));
+    common::step(app, 2);
+}
+
+/// The lit pips and the life icons on display.
+fn status(app: &mut App) -> (usize, usize) {
+    let pips = app.world.query_filtered::<&Children, With<HealthPips>>().single(&app.world).to_vec();
+    let lit = pips.iter().filter(|pip| app.world.get::<UiColor>(**pip).unwrap().0.a() == 1.).count();
+    let icons = app.world.query_filtered::<&Children, With<LifeIcons>>().single(&app.world).len();
+    (lit, icons)
+}
+
+/// Whether the boss bar shows, and how full it is.
+fn boss_bar(app: &mut App) -> (bool, Val) {
+    let visible = app.world.query_filtered::<&Visibility, With<BossBar>>().iter(&app.world).all(|v| v.is_visible);
+    let fill = app.world.query_filtered::<&Style, With<BossBarFill>>().single(&app.world).size.width;
+    (visible, fill)
+}
+
+#[test]
+fn status_bar_follows_the_ship() {
+    let mut app = hud_app();
+    let config = GameConfig::default();
+    assert_eq!(status(&mut app), (config.player.health as usize, config.player.lives as usize));
+
+    let mut player_state = app.world.resource_mut::<PlayerState>();
+    player_state.health -= 1;
+    player_state.lives -= 1;
+    common::step(&mut app, 1);
+    assert_eq!(status(&mut app), (config.player.health as usize - 1, config.player.lives as usize - 1));
+}
+
+#[test]
+fn boss_bar_tracks_the_boss_until_it_goes_down() {
+    let mut app = hud_app();
+    assert!(!boss_bar(&mut app).0);
+
+    common::step(&mut app, 5);
+    let scout = app.world.query_filtered::<&Transform, With<Enemy>>().single(&app.world).translation;
+    shoot(&mut app, scout, 1);
+    common::step(&mut app, 2);
+    assert_eq!(boss_bar(&mut app), (true, Val::Percent(100.)));
+
+    let part = app.world.query_filtered::<&Transform, With<BossPart>>().iter(&app.world).next().unwrap().translation;
+    let hit_points = app.world.query_filtered::<&Health, With<Boss>>().single(&app.world).max;
+    shoot(&mut app, part, hit_points / 2);
+    assert_eq!(boss_bar(&mut app), (true, Val::Percent(50.)));
+
+    shoot(&mut app, part, hit_points);
+    assert!(!boss_bar(&mut app).0);
+}
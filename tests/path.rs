@@ -0,0 +1,57 @@
+use bevy::math::Vec2;
+use invaders::path::{Path, PathEnd, PathShape};
+
+fn close(a: Vec2, b: Vec2) -> bool {
+    a.distance(b) < 1e-3
+}
+
+#[test]
+fn waypoint_paths_are_walked_by_distance_and_hold_at_the_end() {
+    let shape = PathShape::Waypoints(vec![(0., 0.), (1., 0.), (1., 1.)]);
+    let path = Path::new(&shape, &PathEnd::Hold, Vec2::new(100., 100.), false);
+
+    assert!((path.length() - 200.).abs() < 1e-3);
+    assert!(close(path.position(50.), Vec2::new(50., 0.)));
+    assert!(close(path.position(150.), Vec2::new(100., 50.)));
+    assert!(close(path.position(500.), Vec2::new(100., 100.)));
+    assert!(path.finished(500.));
+}
+
+#[test]
+fn looping_paths_wrap_past_their_entry() {
+    let entry = PathShape::Waypoints(vec![(-1., 0.), (0., 0.)]);
+    let then = PathEnd::Loop(PathShape::Waypoints(vec![(0., 0.), (1., 0.)]));
+    let path = Path::new(&entry, &then, Vec2::ONE, false);
+
+    assert!((path.length() - 3.).abs() < 1e-3);
+    assert!(close(path.position(1.5), Vec2::new(0.5, 0.)));
+    assert!(close(path.position(2.5), Vec2::new(0.5, 0.)));
+    assert!(close(path.position(3.5), Vec2::new(0.5, 0.)));
+    assert!(!path.finished(10.));
+}
+
+#[test]
+fn curves_pass_through_their_end_points() {
+    let bezier = PathShape::Bezier(vec![(0., 0.), (0., 1.), (1., 1.), (1., 0.)]);
+    let path = Path::new(&bezier, &PathEnd::Hold, Vec2::ONE, false);
+    assert!(close(path.start(), Vec2::ZERO) && close(path.end(), Vec2::new(1., 0.)));
+
+    let spline = PathShape::CatmullRom(vec![(0., 0.), (1., 1.), (2., 0.)]);
+    assert!(spline.sample().iter().any(|point| close(*point, Vec2::new(1., 1.))));
+}
+
+#[test]
+fn mirrored_paths_are_flipped_left_to_right() {
+    let shape = PathShape::Waypoints(vec![(-1., 0.5), (0.5, 0.5)]);
+    let path = Path::new(&shape, &PathEnd::Hold, Vec2::new(300., 200.), true);
+    assert!(close(path.start(), Vec2::new(300., 100.)));
+    assert!(close(path.end(), Vec2::new(-150., 100.)));
+}
+
+#[test]
+fn malformed_shapes_are_reported() {
+    assert!(PathShape::Bezier(vec![(0., 0.), (1., 1.)]).problem().is_some());
+    assert!(PathShape::Waypoints(vec![]).problem().is_some());
+    assert!(PathShape::Ellipse { center: (0., 0.), radius: (0., 1.) }.problem().is_some());
+    assert!(PathShape::CatmullRom(vec![(0., 0.)]).problem().is_none());
+}
@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use invaders::components::{
+    layers, Collider, Damage, FromEnemy, Invulnerable, Laser, Pickup, PickupKind, Player, PowerUps, ShipStats,
+};
+use invaders::config::GameConfig;
+use invaders::{AppState, PlayerState};
+
+mod common;
+
+fn player(app: &mut App) -> Entity {
+    app.world.query_filtered::<Entity, With<Player>>().single(&app.world)
+}
+
+fn player_translation(app: &mut App) -> Vec3 {
+    let player = player(app);
+    app.world.get::<Transform>(player).unwrap().translation
+}
+
+fn spawn_pickup(app: &mut App, kind: PickupKind) -> Entity {
+    let translation = player_translation(app);
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(translation)))
+        .insert(Pickup(kind))
+        .insert(Collider::aabb((24., 24.), layers::PICKUP, layers::PLAYER))
+        .id()
+}
+
+#[test]
+fn collecting_an_extra_life_adds_one() {
+    let mut app = common::headless_app();
+    let lives = app.world.resource::<PlayerState>().lives;
+    let pickup = spawn_pickup(&mut app, PickupKind::ExtraLife);
+
+    common::step(&mut app, 1);
+
+    assert!(app.world.get_entity(pickup).is_none());
+    assert_eq!(app.world.resource::<PlayerState>().lives, lives + 1);
+}
+
+#[test]
+fn speed_boost_raises_ship_stats_while_it_lasts() {
+    let mut app = common::headless_app();
+    spawn_pickup(&mut app, PickupKind::SpeedBoost);
+    common::step(&mut app, 1);
+
+    let config = GameConfig::default();
+    let player = player(&mut app);
+    assert!(app.world.get::<PowerUps>(player).unwrap().active(PickupKind::SpeedBoost));
+    let boosted = config.player.stats.max_speed * config.pickups.speed_boost;
+    assert_eq!(app.world.get::<ShipStats>(player).unwrap().max_speed, boosted);
+}
+
+#[test]
+fn tuning_changes_made_while_paused_reach_the_ship_on_resume() {
+    let mut app = common::headless_app();
+    app.world.resource_mut::<State<AppState>>().push(AppState::Paused).unwrap();
+    common::step(&mut app, 1);
+
+    app.world.resource_mut::<GameConfig>().player.stats.max_speed = 123.;
+    common::step(&mut app, 1);
+    app.world.resource_mut::<State<AppState>>().pop().unwrap();
+    common::step(&mut app, 1);
+
+    let player = player(&mut app);
+    assert_eq!(app.world.get::<ShipStats>(player).unwrap().max_speed, 123.);
+}
+
+#[test]
+fn shield_breaks_enemy_lasers_without_damage() {
+    let mut app = common::headless_app();
+    spawn_pickup(&mut app, PickupKind::Shield);
+    common::step(&mut app, 1);
+
+    let health = app.world.resource::<PlayerState>().health;
+    let laser = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(player_translation(&mut app))))
+        .insert(Laser)
+        .insert(FromEnemy)
+        .insert(Damage(1))
+        .insert(Collider::aabb((17., 55.), layers::ENEMY_LASER, layers::PLAYER))
+        .id();
+    common::step(&mut app, 1);
+
+    assert!(app.world.get_entity(laser).is_none());
+    assert_eq!(app.world.resource::<PlayerState>().health, health);
+}
+
+#[test]
+fn extra_lives_stop_at_the_maximum() {
+    let mut app = common::headless_app();
+    let max_lives = GameConfig::default().player.max_lives;
+    for _ in 0..max_lives + 1 {
+        spawn_pickup(&mut app, PickupKind::ExtraLife);
+        common::step(&mut app, 1);
+    }
+
+    assert_eq!(app.world.resource::<PlayerState>().lives, max_lives);
+}
+
+#[test]
+fn ship_respawned_after_an_extra_life_is_invulnerable() {
+    let mut app = common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        config.player.health = 1;
+        config.player_respawn_delay = 0.;
+        app.insert_resource(config);
+    });
+    spawn_pickup(&mut app, PickupKind::ExtraLife);
+    common::step(&mut app, 1);
+
+    let translation = player_translation(&mut app);
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(translation)))
+        .insert(Laser)
+        .insert(FromEnemy)
+        .insert(Damage(1))
+        .insert(Collider::aabb((17., 55.), layers::ENEMY_LASER, layers::PLAYER));
+    common::step(&mut app, 2);
+
+    assert_eq!(app.world.resource::<PlayerState>().lives, GameConfig::default().player.lives);
+    let respawned = player(&mut app);
+    assert!(app.world.get::<Invulnerable>(respawned).is_some());
+}
+
+/// Holds right with a speed boost picked up on the way, and returns where the ship ends up.
+fn boosted_run(ticks_per_frame: u32) -> Vec3 {
+    let mut app = common::headless_app_with(|app| common::batch_ticks(app, ticks_per_frame));
+    spawn_pickup(&mut app, PickupKind::SpeedBoost);
+    app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Right);
+    common::step(&mut app, 30 / ticks_per_frame as usize);
+    player_translation(&mut app)
+}
+
+#[test]
+fn speed_boosts_move_the_ship_the_same_however_ticks_fall_into_frames() {
+    assert_eq!(boosted_run(1), boosted_run(3));
+}
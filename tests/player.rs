@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use invaders::components::{layers, Collider, Damage, FromEnemy, Invulnerable, Laser, Player};
+use invaders::config::GameConfig;
+use invaders::{AppState, PlayerState, RunEnded, TIME_STEP};
+
+mod common;
+
+/// One-hit ships, two lives and an immediate respawn.
+fn fragile_app() -> App {
+    common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        config.player.lives = 2;
+        config.player.health = 1;
+        config.player_respawn_delay = 0.;
+        app.insert_resource(config);
+    })
+}
+
+fn player(app: &mut App) -> Option<Entity> {
+    app.world.query_filtered::<Entity, With<Player>>().iter(&app.world).next()
+}
+
+/// Spawns an enemy laser on top of the player.
+fn shoot_player(app: &mut App) -> Entity {
+    let player = player(app).expect("player should be spawned");
+    let translation = app.world.get::<Transform>(player).unwrap().translation;
+    app.world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_translation(translation)))
+        .insert(Laser)
+        .insert(FromEnemy)
+        .insert(Damage(1))
+        .insert(Collider::aabb((17., 55.), layers::ENEMY_LASER, layers::PLAYER))
+        .id()
+}
+
+#[test]
+fn losing_a_ship_costs_a_life_and_respawns_invulnerable() {
+    let mut app = fragile_app();
+    shoot_player(&mut app);
+    common::step(&mut app, 1);
+
+    assert!(player(&mut app).is_none());
+    assert_eq!(app.world.resource::<PlayerState>().lives, 1);
+    assert!(!app.world.resource::<RunEnded>().0);
+
+    common::step(&mut app, 1);
+    let respawned = player(&mut app).expect("player should respawn");
+    assert!(app.world.get::<Invulnerable>(respawned).is_some());
+
+    let laser = shoot_player(&mut app);
+    common::step(&mut app, 1);
+
+    assert_eq!(player(&mut app), Some(respawned));
+    assert!(app.world.get_entity(laser).is_some());
+    assert_eq!(app.world.resource::<PlayerState>().lives, 1);
+}
+
+#[test]
+fn losing_the_last_life_ends_the_run() {
+    let mut app = fragile_app();
+    shoot_player(&mut app);
+    common::step(&mut app, 2);
+
+    let respawned = player(&mut app).unwrap();
+    app.world.entity_mut(respawned).remove::<Invulnerable>();
+    shoot_player(&mut app);
+    common::step(&mut app, 2);
+
+    assert_eq!(app.world.resource::<PlayerState>().lives, 0);
+    assert!(player(&mut app).is_none());
+    assert_eq!(app.world.resource::<State<AppState>>().current(), &AppState::GameOver);
+}
+
+#[test]
+fn next_ship_comes_in_after_exactly_the_respawn_delay() {
+    let mut app = common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        config.player.health = 1;
+        app.insert_resource(config);
+    });
+    let delay = GameConfig::default().player_respawn_delay as f32;
+    let ticks = (delay / TIME_STEP).round() as usize;
+
+    shoot_player(&mut app);
+    common::step(&mut app, 1);
+    assert!(player(&mut app).is_none());
+
+    common::step(&mut app, ticks - 1);
+    assert!(player(&mut app).is_none(), "the ship should not be back before the delay is over");
+    common::step(&mut app, 1);
+    assert!(player(&mut app).is_some(), "the ship should be back once the delay is over");
+}
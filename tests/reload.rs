@@ -0,0 +1,83 @@
+use bevy::asset::AssetPlugin;
+use bevy::prelude::*;
+use invaders::components::{Enemy, EnemyGun, EnemyKind, Player, ShipStats};
+use invaders::config::{ConfigReloadPlugin, GameConfig};
+
+mod common;
+
+/// Not on disk, so that only the configs the tests set ever load.
+const PATH: &str = "reload-test.ron";
+
+fn reload_app() -> App {
+    let mut app = common::headless_app_with(|app| {
+        app.add_plugin(AssetPlugin).add_plugin(ConfigReloadPlugin { asset_path: PATH.to_string() });
+    });
+    // The first version set is the one loaded at startup, and only creates the asset.
+    reload(&mut app, GameConfig::default());
+    app
+}
+
+/// Stands in for an edit of the tuning file: the asset is modified, then picked up.
+fn reload(app: &mut App, config: GameConfig) {
+    let handle = app.world.resource::<AssetServer>().get_handle::<GameConfig, _>(PATH);
+    app.world.resource_mut::<Assets<GameConfig>>().set_untracked(handle, config);
+    common::step(app, 2);
+}
+
+#[test]
+fn modified_configs_replace_the_running_one() {
+    let mut app = reload_app();
+    let mut config = GameConfig::default();
+    config.base_speed = 321.;
+    reload(&mut app, config);
+
+    assert_eq!(app.world.resource::<GameConfig>().base_speed, 321.);
+}
+
+#[test]
+fn modified_configs_reach_the_ship() {
+    let mut app = reload_app();
+    let mut config = GameConfig::default();
+    config.player.stats.max_speed = 123.;
+    reload(&mut app, config);
+
+    let stats = app.world.query_filtered::<&ShipStats, With<Player>>().single(&app.world);
+    assert_eq!(stats.max_speed, 123.);
+}
+
+#[test]
+fn invalid_configs_leave_the_running_one_in_place() {
+    let mut app = reload_app();
+    let mut config = GameConfig::default();
+    config.base_speed = 0.;
+    reload(&mut app, config);
+
+    assert_eq!(app.world.resource::<GameConfig>().base_speed, GameConfig::default().base_speed);
+}
+
+#[test]
+fn enemies_of_a_kind_reloaded_away_live_on_without_firing() {
+    let mut app = reload_app();
+    let mut config = GameConfig::default();
+    config.enemies.remove("gunner");
+    for wave in &mut config.waves {
+        wave.enemies.retain(|(name, _)| name != "gunner");
+    }
+    config.validate().unwrap();
+    reload(&mut app, config);
+
+    // Left over from before the reload, and due to fire on the next tick.
+    let kind = GameConfig::default().enemies["gunner"].kind("gunner");
+    let gunner = app
+        .world
+        .spawn()
+        .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(0., 200., 10.)))
+        .insert(Enemy)
+        .insert(kind)
+        .insert(EnemyGun { timer: Timer::from_seconds(0., false), burst_left: 1, spiral: 0. })
+        .id();
+    common::step(&mut app, 60);
+
+    assert!(app.world.get::<EnemyKind>(gunner).is_some());
+    assert!(app.world.get::<EnemyGun>(gunner).is_none());
+}
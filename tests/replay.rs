@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use invaders::replay::{InputRecording, Recorder, Replay};
+use invaders::rng::GameRng;
+use invaders::PlayerState;
+
+mod common;
+
+const SEED: u64 = 42;
+/// Scripted ticks; with the one entering `Playing`, a multiple of `REPLAY_BATCH`.
+const TICKS: usize = 1199;
+/// Ticks per frame while replaying, as on a client running slow.
+const REPLAY_BATCH: u32 = 3;
+
+/// Sweeps left and right while tapping fire, as a stand-in for a human session.
+fn scripted_keys(tick: usize) -> Vec<KeyCode> {
+    let mut keys = Vec::new();
+    keys.push(if (tick / 120) % 2 == 0 { KeyCode::Left } else { KeyCode::Right });
+    if tick % 8 == 0 {
+        keys.push(KeyCode::Space);
+    }
+    keys
+}
+
+fn record_run() -> (InputRecording, i64) {
+    let mut app = common::headless_app_with(|app| {
+        app.insert_resource(GameRng::new(SEED))
+            .insert_resource(Recorder::new(SEED, None));
+    });
+
+    for tick in 0..TICKS {
+        let keys = scripted_keys(tick);
+        let mut kb = app.world.resource_mut::<Input<KeyCode>>();
+        kb.clear();
+        for key in [KeyCode::Left, KeyCode::Right, KeyCode::Space] {
+            if keys.contains(&key) {
+                kb.press(key);
+            } else {
+                kb.release(key);
+            }
+        }
+        app.update();
+    }
+
+    let recording = app.world.resource::<Recorder>().recording.clone();
+    (recording, app.world.resource::<PlayerState>().score)
+}
+
+fn replay_run(recording: InputRecording) -> i64 {
+    let mut app = common::headless_app_with(|app| {
+        app.insert_resource(GameRng::new(recording.seed))
+            .insert_resource(Replay::new(recording.clone()));
+        common::batch_ticks(app, REPLAY_BATCH);
+    });
+    // Setup already ran the first frame in `Playing`.
+    common::step(&mut app, recording.ticks.len() / REPLAY_BATCH as usize - 1);
+    assert!(app.world.resource::<Replay>().finished());
+    app.world.resource::<PlayerState>().score
+}
+
+#[test]
+fn recording_round_trips_through_encoding() {
+    let recording = InputRecording { seed: SEED, ticks: vec![0, 0, 0, 1, 1, 16, 0, 3] };
+    let decoded = InputRecording::decode(&recording.encode()).unwrap();
+    assert_eq!(decoded, recording);
+}
+
+#[test]
+fn long_runs_are_run_length_encoded() {
+    let recording = InputRecording { seed: SEED, ticks: vec![1; 1000] };
+    let encoded = recording.encode();
+    assert!(encoded.len() < 40);
+    assert_eq!(InputRecording::decode(&encoded).unwrap(), recording);
+}
+
+#[test]
+fn corrupt_recording_is_rejected() {
+    assert!(InputRecording::decode(b"nope").is_err());
+    let mut encoded = InputRecording { seed: SEED, ticks: vec![2; 10] }.encode();
+    encoded.pop();
+    assert!(InputRecording::decode(&encoded).is_err());
+}
+
+#[test]
+fn replay_reproduces_recorded_score() {
+    let (recording, score) = record_run();
+    // One extra tick is recorded by the update that enters `Playing`.
+    assert_eq!(recording.ticks.len(), TICKS + 1);
+    assert!(score > 0, "the scripted run should shoot something down");
+    assert_eq!(replay_run(recording), score);
+}
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use invaders::components::{
+    layers, Boss, BossPart, Collider, Damage, Enemy, Explosion, FromEnemy, Invulnerable, Laser, Pickup, Player,
+};
+use invaders::rng::GameRng;
+use invaders::wave::CurrentWave;
+use invaders::{AppState, EnemyCount, PlayerState};
+use rand::RngCore;
+
+mod common;
+
+const SEED: u64 = 7;
+
+fn seeded_app() -> App {
+    common::headless_app_with(|app| {
+        app.insert_resource(GameRng::new(SEED));
+    })
+}
+
+/// What a run starts from: the ship, the enemies, the wave and the next random draw.
+fn run_start(app: &mut App) -> (i64, u32, i64, u32, u32, u32, u64) {
+    let player_state = app.world.resource::<PlayerState>();
+    let (health, lives, score) = (player_state.health, player_state.lives, player_state.score);
+    let enemy_count = app.world.resource::<EnemyCount>().0;
+    let wave = app.world.resource::<CurrentWave>();
+    let (number, spawned) = (wave.number, wave.spawned);
+    let draw = app.world.resource_mut::<GameRng>().next_u64();
+    (health, lives, score, enemy_count, number, spawned, draw)
+}
+
+fn game_over(app: &App) -> bool {
+    app.world.resource::<State<AppState>>().current() == &AppState::GameOver
+}
+
+/// Plays on until the run is lost, destroying the ship with its last life as soon as it
+/// is up and hittable.
+fn lose_the_run(app: &mut App) {
+    while !game_over(app) {
+        let mut query = app.world.query_filtered::<Entity, (With<Player>, Without<Invulnerable>)>();
+        if let Some(player) = query.iter(&app.world).next() {
+            app.world.resource_mut::<PlayerState>().lives = 1;
+            let translation = app.world.get::<Transform>(player).unwrap().translation;
+            app.world
+                .spawn()
+                .insert_bundle(TransformBundle::from_transform(Transform::from_translation(translation)))
+                .insert(Laser)
+                .insert(FromEnemy)
+                .insert(Damage(100))
+                .insert(Collider::aabb((17., 55.), layers::ENEMY_LASER, layers::PLAYER));
+        }
+        common::step(app, 1);
+    }
+}
+
+#[test]
+fn restarting_after_game_over_starts_like_a_fresh_run() {
+    let expected = run_start(&mut seeded_app());
+
+    let mut app = seeded_app();
+    common::step(&mut app, 300);
+    lose_the_run(&mut app);
+    let mut query = app.world.query_filtered::<Entity, Or<(
+        With<Enemy>,
+        With<Boss>,
+        With<BossPart>,
+        With<Laser>,
+        With<Explosion>,
+        With<Player>,
+        With<Pickup>,
+    )>>();
+    let left_over: Vec<Entity> = query.iter(&app.world).collect();
+    assert!(!left_over.is_empty(), "the run should leave something behind to clear");
+
+    app.world.resource_mut::<State<AppState>>().set(AppState::Playing).unwrap();
+    common::step(&mut app, 1);
+
+    assert!(left_over.iter().all(|entity| app.world.get_entity(*entity).is_none()));
+    assert_eq!(run_start(&mut app), expected);
+}
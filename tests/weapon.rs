@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use invaders::components::{FromPlayer, Laser, Player, Velocity, Weapon};
+use invaders::config::GameConfig;
+
+mod common;
+
+fn hold_fire(app: &mut App) {
+    app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Space);
+}
+
+fn release_fire(app: &mut App) {
+    app.world.resource_mut::<Input<KeyCode>>().release(KeyCode::Space);
+}
+
+fn player_lasers(app: &mut App) -> Vec<(f32, f32)> {
+    app.world
+        .query_filtered::<&Velocity, (With<Laser>, With<FromPlayer>)>()
+        .iter(&app.world)
+        .map(|velocity| (velocity.x, velocity.y))
+        .collect()
+}
+
+#[test]
+fn a_press_fires_one_volley_without_autofire() {
+    let mut app = common::headless_app();
+    hold_fire(&mut app);
+    common::step(&mut app, 30);
+
+    assert_eq!(player_lasers(&mut app).len(), 2);
+}
+
+#[test]
+fn autofire_weapons_keep_firing_at_their_interval() {
+    let mut app = common::headless_app();
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    app.world.get_mut::<Weapon>(player).unwrap().equip("pulse");
+
+    hold_fire(&mut app);
+    common::step(&mut app, 1);
+    assert_eq!(player_lasers(&mut app).len(), 1);
+
+    let interval = GameConfig::default().weapons["pulse"].levels[0].fire_interval;
+    common::step(&mut app, (interval / invaders::TIME_STEP).ceil() as usize);
+    assert_eq!(player_lasers(&mut app).len(), 2);
+
+    release_fire(&mut app);
+    common::step(&mut app, 30);
+    assert_eq!(player_lasers(&mut app).len(), 2);
+}
+
+#[test]
+fn upgraded_weapons_fire_their_next_level_at_laser_speed() {
+    let mut app = common::headless_app_with(|app| {
+        let mut config = GameConfig::default();
+        config.player.stats.laser_speed = 2.;
+        app.insert_resource(config);
+    });
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    app.world.get_mut::<Weapon>(player).unwrap().upgrade();
+
+    hold_fire(&mut app);
+    common::step(&mut app, 1);
+
+    let lasers = player_lasers(&mut app);
+    assert_eq!(lasers.len(), 4);
+    for (x, y) in lasers {
+        assert!(((x * x + y * y).sqrt() - 2.).abs() < 1e-4);
+    }
+}